@@ -1,16 +1,47 @@
 use near_sdk::env;
 use serde::Serialize;
 
+/// A NEP-297 event, serialized with its `standard` tag.
 #[derive(Serialize, Debug)]
 #[serde(tag = "standard")]
 #[must_use = "don't forget to `.emit()` this event"]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum NearEvent<'a> {
+pub enum NearEvent<'a> {
     Nep246(crate::multi_token::events::Nep246Event<'a>),
+    /// An event of any other standard, built with [`NearEvent::custom`].
+    #[serde(untagged)]
+    Custom(CustomEvent<'a>),
+}
+
+/// Envelope of an event of a standard this crate has no types for, such as NEP-141 for a
+/// contract that is also a fungible token.
+#[derive(Serialize, Debug)]
+pub struct CustomEvent<'a> {
+    pub standard: &'a str,
+    pub version: &'a str,
+    pub event: &'a str,
+    pub data: serde_json::Value,
 }
 
 impl<'a> NearEvent<'a> {
-    fn to_json_string(&self) -> String {
+    /// Builds an event of an arbitrary `standard`, logged with the same NEP-297 envelope
+    /// as NEP-246 events.
+    pub fn custom(
+        standard: &'a str,
+        version: &'a str,
+        event: &'a str,
+        data: serde_json::Value,
+    ) -> Self {
+        NearEvent::Custom(CustomEvent {
+            standard,
+            version,
+            event,
+            data,
+        })
+    }
+
+    /// Serializes the event to its NEP-297 JSON, without the `EVENT_JSON:` prefix.
+    pub fn to_json_string(&self) -> String {
         // Events cannot fail to serialize so fine to panic on error
         #[allow(clippy::redundant_closure)]
         serde_json::to_string(self)
@@ -18,13 +49,67 @@ impl<'a> NearEvent<'a> {
             .unwrap_or_else(|| env::abort())
     }
 
-    fn to_json_event_string(&self) -> String {
+    /// Serializes the event to the exact line [`emit`](NearEvent::emit) logs.
+    pub fn to_event_json_string(&self) -> String {
         format!("EVENT_JSON:{}", self.to_json_string())
     }
 
     /// Logs the event to the host. This is required to ensure that the event is triggered
     /// and to consume the event.
-    pub(crate) fn emit(self) {
-        near_sdk::env::log_str(&self.to_json_event_string());
+    pub fn emit(self) {
+        let line = self.to_event_json_string();
+        self.emit_line(&line);
+    }
+
+    /// Same as [`emit`](NearEvent::emit), but serializes the line into `buffer` instead of a
+    /// fresh `String`, so contracts logging many events can reuse one allocation. The
+    /// previous contents of `buffer` are discarded.
+    pub fn emit_with_buffer(self, buffer: &mut String) {
+        let mut bytes = std::mem::take(buffer).into_bytes();
+        bytes.clear();
+        bytes.extend_from_slice(b"EVENT_JSON:");
+        // Events cannot fail to serialize so fine to panic on error
+        #[allow(clippy::redundant_closure)]
+        serde_json::to_writer(&mut bytes, &self)
+            .ok()
+            .unwrap_or_else(|| env::abort());
+        // serde_json only writes valid UTF-8.
+        *buffer = String::from_utf8(bytes)
+            .ok()
+            .unwrap_or_else(|| env::abort());
+        self.emit_line(buffer);
+    }
+
+    /// Logs `line`, which must be this event's [`to_event_json_string`](NearEvent::to_event_json_string),
+    /// for callers that already serialized it.
+    pub(crate) fn emit_line(self, line: &str) {
+        #[cfg(feature = "event-metrics")]
+        match &self {
+            NearEvent::Nep246(event) => crate::multi_token::metrics::record(event.event_name()),
+            NearEvent::Custom(_) => {}
+        }
+        near_sdk::env::log_str(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils;
+    use serde_json::json;
+
+    #[test]
+    fn custom_event_envelope() {
+        NearEvent::custom(
+            "nep141",
+            "1.0.0",
+            "ft_mint",
+            json!([{"owner_id": "bob", "amount": "100"}]),
+        )
+        .emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"amount":"100","owner_id":"bob"}]}"#
+        );
     }
 }
@@ -1,2 +1,5 @@
-mod event;
-mod multi_token;
+#[cfg(feature = "events")]
+pub mod event;
+pub mod multi_token;
+#[cfg(all(any(test, feature = "testing"), not(target_arch = "wasm32")))]
+pub mod testing;
@@ -0,0 +1,834 @@
+use crate::multi_token::approval::{ext_mt_approval_receiver, Approvals, MultiTokenApproval};
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::error::{abort, MtError};
+#[cfg(feature = "events")]
+use crate::multi_token::events::MtApprove;
+use crate::multi_token::token::TokenId;
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, require, AccountId, Balance, PromiseOrValue};
+
+fn expect_approvals<T>(approvals_by_id: Option<T>) -> T {
+    match approvals_by_id {
+        Some(approvals_by_id) => approvals_by_id,
+        None => abort(MtError::ApprovalNotSupported),
+    }
+}
+
+fn store_approvals(
+    approvals_by_id: &mut LookupMap<(AccountId, TokenId), Approvals>,
+    key: &(AccountId, TokenId),
+    approvals: &Approvals,
+) {
+    if approvals.is_empty() {
+        approvals_by_id.remove(key);
+    } else {
+        approvals_by_id.insert(key, approvals);
+    }
+}
+
+impl MultiToken {
+    /// Returns how much of `token_id` `owner_id` approved `account_id` to transfer, or 0.
+    /// With `approval_id`, only an approval with that ID counts.
+    pub fn internal_approved_amount(
+        &self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        approval_id: Option<u64>,
+    ) -> Balance {
+        self.approvals_by_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&(owner_id.clone(), token_id.clone())))
+            .and_then(|approvals| approvals.get(account_id).copied())
+            .filter(|(id, _)| approval_id.is_none_or(|approval_id| approval_id == *id))
+            .map_or(0, |(_, amount)| amount)
+    }
+
+    /// Checks that `owner_id` approved `account_id`, under `approval_id`, to transfer at
+    /// least `amount` of `token_id`, and returns the approved amount.
+    pub fn internal_check_approval(
+        &self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        approval_id: u64,
+        amount: Balance,
+    ) -> Result<Balance, MtError> {
+        let approvals_by_id = self
+            .approvals_by_id
+            .as_ref()
+            .ok_or(MtError::ApprovalNotSupported)?;
+        let (id, approved_amount) = approvals_by_id
+            .get(&(owner_id.clone(), token_id.clone()))
+            .and_then(|approvals| approvals.get(account_id).copied())
+            .ok_or(MtError::NotApproved)?;
+        if id != approval_id {
+            return Err(MtError::InvalidApprovalId);
+        }
+        if amount > approved_amount {
+            return Err(MtError::ExceedsApprovedAmount);
+        }
+        Ok(approved_amount)
+    }
+
+    /// Takes `amount` out of what `owner_id` approved `account_id` to transfer of
+    /// `token_id`, removing the approval once it is used up. Panics if there is no such
+    /// approval, it has another ID, or it covers less than `amount`.
+    pub fn internal_spend_approval(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        approval_id: u64,
+        amount: Balance,
+    ) {
+        match self.internal_check_approval(owner_id, token_id, account_id, approval_id, amount) {
+            Ok(approved_amount) => {
+                let initial_storage_usage = env::storage_usage();
+                let approvals_by_id = expect_approvals(self.approvals_by_id.as_mut());
+                let key = (owner_id.clone(), token_id.clone());
+                let mut approvals = approvals_by_id.get(&key).unwrap_or_default();
+                if amount == approved_amount {
+                    approvals.remove(account_id);
+                } else {
+                    approvals.insert(account_id.clone(), (approval_id, approved_amount - amount));
+                }
+                store_approvals(approvals_by_id, &key, &approvals);
+                self.internal_track_storage(owner_id, initial_storage_usage);
+            }
+            Err(err) => abort(err),
+        }
+    }
+
+    /// Credits `amount` back to the approval `approval_id` that `owner_id` gave
+    /// `account_id` for `token_id`, re-creating it if it was used up. An approval the
+    /// owner has replaced since, under a newer ID, is left alone.
+    pub fn internal_restore_approval(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        approval_id: u64,
+        amount: Balance,
+    ) {
+        let approvals_by_id = match self.approvals_by_id.as_mut() {
+            Some(approvals_by_id) => approvals_by_id,
+            None => return,
+        };
+        let key = (owner_id.clone(), token_id.clone());
+        let mut approvals = approvals_by_id.get(&key).unwrap_or_default();
+        let restored = match approvals.get(account_id).copied() {
+            Some((id, approved_amount)) if id == approval_id => {
+                (id, approved_amount.saturating_add(amount))
+            }
+            Some(_) => return,
+            None => (approval_id, amount),
+        };
+        approvals.insert(account_id.clone(), restored);
+        store_approvals(approvals_by_id, &key, &approvals);
+    }
+
+    /// Caps every approval `owner_id` gave for `token_id` at the owner's balance, e.g.
+    /// after some of the token was transferred or burned. Does nothing without approval management.
+    pub fn internal_cap_approvals(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        if self.approvals_by_id.is_none() {
+            return;
+        }
+        let balance = self.internal_balance_of(owner_id, token_id);
+        self.internal_cap_approvals_at(owner_id, token_id, balance);
+    }
+
+    /// Same as [`internal_cap_approvals`](MultiToken::internal_cap_approvals), with the
+    /// owner's balance already read.
+    pub(crate) fn internal_cap_approvals_at(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+    ) {
+        let approvals_by_id = match self.approvals_by_id.as_mut() {
+            Some(approvals_by_id) => approvals_by_id,
+            None => return,
+        };
+        let key = (owner_id.clone(), token_id.clone());
+        let mut approvals = match approvals_by_id.get(&key) {
+            Some(approvals) => approvals,
+            None => return,
+        };
+        for (_, approved_amount) in approvals.values_mut() {
+            *approved_amount = (*approved_amount).min(balance);
+        }
+        store_approvals(approvals_by_id, &key, &approvals);
+    }
+}
+
+impl MultiTokenApproval for MultiToken {
+    fn mt_approve(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> PromiseOrValue<Vec<u64>> {
+        require!(
+            env::attached_deposit() >= 1,
+            "Requires attached deposit of at least 1 yoctoNEAR"
+        );
+        self.assert_not_paused();
+        require!(!token_ids.is_empty(), "No token_ids provided");
+        require!(
+            token_ids.len() == amounts.len(),
+            "token_ids and amounts must have the same length"
+        );
+        let owner_id = env::predecessor_account_id();
+        for token_id in &token_ids {
+            require!(
+                self.internal_balance_of(&owner_id, token_id) > 0,
+                "Predecessor must be the token owner"
+            );
+        }
+        let initial_storage_usage = env::storage_usage();
+        let approvals_by_id = expect_approvals(self.approvals_by_id.as_mut());
+        let mut approval_ids = Vec::with_capacity(token_ids.len());
+        for (token_id, amount) in token_ids.iter().zip(&amounts) {
+            let key = (owner_id.clone(), token_id.clone());
+            let mut approvals = approvals_by_id.get(&key).unwrap_or_default();
+            let approval_id = self.next_approval_id;
+            approvals.insert(account_id.clone(), (approval_id, amount.0));
+            store_approvals(approvals_by_id, &key, &approvals);
+            self.next_approval_id += 1;
+            approval_ids.push(approval_id);
+        }
+        self.internal_track_storage(&owner_id, initial_storage_usage);
+        #[cfg(feature = "events")]
+        {
+            let ids: Vec<&str> = token_ids.iter().map(TokenId::as_str).collect();
+            let amounts: Vec<String> = amounts.iter().map(|amount| amount.0.to_string()).collect();
+            let amounts: Vec<&str> = amounts.iter().map(String::as_str).collect();
+            let approvals: Vec<MtApprove> = approval_ids
+                .iter()
+                .enumerate()
+                .map(|(i, &approval_id)| MtApprove {
+                    owner_id: &owner_id,
+                    approved_id: &account_id,
+                    token_ids: &ids[i..=i],
+                    amounts: &amounts[i..=i],
+                    approval_id: Some(approval_id),
+                    memo: None,
+                })
+                .collect();
+            MtApprove::emit_many(&approvals);
+        }
+
+        // if given `msg`, schedule call to `mt_on_approve` and return it. Else, return the
+        // assigned approval IDs.
+        match msg {
+            Some(msg) => ext_mt_approval_receiver::ext(account_id)
+                .mt_on_approve(token_ids, amounts, owner_id, approval_ids, msg)
+                .into(),
+            None => PromiseOrValue::Value(approval_ids),
+        }
+    }
+
+    fn mt_revoke(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        account_id: AccountId,
+    ) -> Vec<bool> {
+        assert_one_yocto();
+        self.assert_not_paused();
+        require!(
+            token_ids.len() == amounts.len(),
+            "token_ids and amounts must have the same length"
+        );
+        let owner_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+        let approvals_by_id = expect_approvals(self.approvals_by_id.as_mut());
+        let revoked = token_ids
+            .into_iter()
+            .zip(amounts)
+            .map(|(token_id, amount)| {
+                let key = (owner_id.clone(), token_id);
+                let mut approvals = approvals_by_id.get(&key).unwrap_or_default();
+                match approvals.get(&account_id).copied() {
+                    Some((id, approved_amount)) => {
+                        let remaining = approved_amount.saturating_sub(amount.0);
+                        if remaining == 0 {
+                            approvals.remove(&account_id);
+                        } else {
+                            approvals.insert(account_id.clone(), (id, remaining));
+                        }
+                        store_approvals(approvals_by_id, &key, &approvals);
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .collect();
+        self.internal_track_storage(&owner_id, initial_storage_usage);
+        revoked
+    }
+
+    fn mt_revoke_all(&mut self, token_ids: Vec<TokenId>) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let owner_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+        let approvals_by_id = expect_approvals(self.approvals_by_id.as_mut());
+        for token_id in token_ids {
+            approvals_by_id.remove(&(owner_id.clone(), token_id));
+        }
+        self.internal_track_storage(&owner_id, initial_storage_usage);
+    }
+
+    fn mt_is_approved(
+        &self,
+        token_ids: Vec<TokenId>,
+        owner_id: AccountId,
+        approved_account_id: AccountId,
+        amounts: Vec<U128>,
+        approval_ids: Option<Vec<u64>>,
+    ) -> bool {
+        require!(!token_ids.is_empty(), "No token_ids provided");
+        require!(
+            amounts.is_empty() || token_ids.len() == amounts.len(),
+            "token_ids and amounts must have the same length"
+        );
+        if let Some(approval_ids) = &approval_ids {
+            require!(
+                approval_ids.len() == token_ids.len(),
+                "token_ids and approval_ids must have the same length"
+            );
+        }
+        token_ids.iter().enumerate().all(|(i, token_id)| {
+            let approval_id = approval_ids.as_ref().map(|ids| ids[i]);
+            // Without an amount, or for 0, any nonzero approval will do.
+            let amount = amounts.get(i).map_or(1, |amount| amount.0.max(1));
+            self.internal_approved_amount(&owner_id, token_id, &approved_account_id, approval_id)
+                >= amount
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::core::MultiTokenCore;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn as_account(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(1)
+            .build());
+    }
+
+    /// Alice holds 100 gold and has approved bob for 5 of it under approval ID 0.
+    fn setup() -> MultiToken {
+        as_account(accounts(0));
+        let mut mt = MultiToken::new(b"m").with_approvals();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        mt.mt_approve(vec!["gold".into()], vec![U128(5)], accounts(1), None);
+        mt
+    }
+
+    #[test]
+    fn approve_assigns_fresh_ids() {
+        let mut mt = setup();
+        mt.mt_approve(vec!["gold".into()], vec![U128(7)], accounts(2), None);
+        assert_eq!(mt.next_approval_id, 2);
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_approve","data":[{"owner_id":"alice","approved_id":"charlie","token_ids":["gold"],"amounts":["7"],"approval_id":1}]}"#
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(2), Some(1)),
+            7
+        );
+        assert!(mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(5)],
+            Some(vec![0])
+        ));
+        assert!(!mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(6)],
+            None
+        ));
+    }
+
+    #[test]
+    fn approve_returns_the_assigned_ids() {
+        let mut mt = setup();
+        mt.internal_mint(
+            &accounts(0),
+            &["silver".into(), "sword".into()],
+            &[10, 1],
+            None,
+        );
+        let approval_ids = mt.mt_approve(
+            vec!["gold".into(), "silver".into(), "sword".into()],
+            vec![U128(1), U128(2), U128(1)],
+            accounts(2),
+            None,
+        );
+        match approval_ids {
+            PromiseOrValue::Value(approval_ids) => assert_eq!(approval_ids, [1, 2, 3]),
+            PromiseOrValue::Promise(_) => panic!("Expected the approval IDs"),
+        }
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"silver".into(), &accounts(2), Some(2)),
+            2
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No token_ids provided")]
+    fn approve_requires_token_ids() {
+        setup().mt_approve(vec![], vec![], accounts(2), None);
+    }
+
+    /// Alice has also approved charlie for 100 gold.
+    fn approved_for_100() -> MultiToken {
+        let mut mt = setup();
+        mt.mt_approve(vec!["gold".into()], vec![U128(100)], accounts(2), None);
+        mt
+    }
+
+    fn is_approved_for(mt: &MultiToken, amounts: Vec<U128>) -> bool {
+        mt.mt_is_approved(vec!["gold".into()], accounts(0), accounts(2), amounts, None)
+    }
+
+    #[test]
+    fn is_approved_for_less_than_approved() {
+        assert!(is_approved_for(&approved_for_100(), vec![U128(50)]));
+    }
+
+    #[test]
+    fn is_approved_for_exactly_approved() {
+        assert!(is_approved_for(&approved_for_100(), vec![U128(100)]));
+    }
+
+    #[test]
+    fn is_not_approved_for_more_than_approved() {
+        assert!(!is_approved_for(&approved_for_100(), vec![U128(150)]));
+    }
+
+    #[test]
+    fn is_approved_without_amounts_checks_for_any_approval() {
+        let mt = approved_for_100();
+        assert!(is_approved_for(&mt, vec![]));
+        assert!(is_approved_for(&mt, vec![U128(0)]));
+        assert!(!mt.mt_is_approved(vec!["gold".into()], accounts(0), accounts(3), vec![], None));
+    }
+
+    #[test]
+    #[should_panic(expected = "No token_ids provided")]
+    fn is_approved_requires_token_ids() {
+        let _ = setup().mt_is_approved(vec![], accounts(0), accounts(1), vec![], None);
+    }
+
+    #[test]
+    fn is_approved_checks_the_given_owner() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(2), &["gold".into()], &[10], None);
+        assert!(mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(5)],
+            None
+        ));
+        assert!(!mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(2),
+            accounts(1),
+            vec![U128(1)],
+            None
+        ));
+    }
+
+    #[test]
+    fn token_lists_approved_accounts() {
+        let mut mt = setup();
+        let approved = |mt: &MultiToken| {
+            mt.mt_token(vec!["gold".into()])[0]
+                .clone()
+                .unwrap()
+                .approved_account_ids
+        };
+        assert_eq!(approved(&mt), Some([(accounts(1), 0)].into()));
+        mt.internal_mint(&accounts(2), &["gold".into()], &[1], None);
+        assert_eq!(approved(&mt), None);
+    }
+
+    #[test]
+    fn spender_uses_part_of_approval() {
+        let mut mt = setup();
+        as_account(accounts(1));
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(3),
+            Some((accounts(0), 0)),
+            None,
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(97));
+        assert_eq!(mt.mt_balance_of(accounts(2), "gold".into()), U128(3));
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), None),
+            2
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"charlie","token_ids":["gold"],"amounts":["3"],"authorized_id":"bob","approval_ids":[0]}]}"#
+        );
+
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(2),
+            Some((accounts(0), 0)),
+            None,
+        );
+        assert!(mt
+            .approvals_by_id
+            .as_ref()
+            .unwrap()
+            .get(&(accounts(0), "gold".into()))
+            .is_none());
+    }
+
+    #[test]
+    fn spender_transfer_call_is_attributed_to_the_owner() {
+        let mut mt = setup();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(1)
+            .prepaid_gas(near_sdk::Gas(300_000_000_000_000))
+            .build());
+        let _ = mt.mt_transfer_call(
+            accounts(2),
+            "gold".into(),
+            U128(5),
+            Some((accounts(0), 0)),
+            None,
+            "".into(),
+        );
+        #[cfg(feature = "events")]
+        crate::testing::assert_event(near_sdk::serde_json::json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_transfer",
+            "data": [{
+                "old_owner_id": "alice",
+                "new_owner_id": "charlie",
+                "token_ids": ["gold"],
+                "amounts": ["5"],
+                "authorized_id": "bob",
+                "approval_ids": [0],
+            }],
+        }));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        match &receipts[0].actions[0] {
+            near_sdk::mock::VmAction::FunctionCall { args, .. } => assert_eq!(
+                std::str::from_utf8(args).unwrap(),
+                r#"{"sender_id":"bob","previous_owner_id":"alice","token_ids":["gold"],"amounts":["5"],"msg":""}"#
+            ),
+            action => panic!("unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn reverted_transfer_call_restores_the_approval() {
+        let mut mt = setup();
+        let key = (accounts(0), TokenId::from("gold"));
+        let approvals = |mt: &MultiToken| mt.approvals_by_id.as_ref().unwrap().get(&key);
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(1)
+            .prepaid_gas(near_sdk::Gas(300_000_000_000_000))
+            .build());
+        let _ = mt.internal_transfer_call(
+            accounts(2),
+            "gold".into(),
+            U128(5),
+            Some((accounts(0), 0)),
+            None,
+            "".into(),
+            Default::default(),
+        );
+        assert_eq!(approvals(&mt), None);
+
+        // Resolve with the approvals the transfer handed to `mt_resolve_transfer`.
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let spent = match &receipts[1].actions[0] {
+            near_sdk::mock::VmAction::FunctionCall { args, .. } => {
+                let args: near_sdk::serde_json::Value =
+                    near_sdk::serde_json::from_slice(args).unwrap();
+                near_sdk::serde_json::from_value(args["approvals"].clone()).unwrap()
+            }
+            action => panic!("unexpected action {:?}", action),
+        };
+        testing_env!(
+            VMContextBuilder::new().build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        mt.internal_resolve_transfer(
+            &accounts(0),
+            accounts(2),
+            vec!["gold".into()],
+            vec![U128(5)],
+            spent,
+        );
+        assert_eq!(
+            approvals(&mt),
+            Some(std::collections::HashMap::from([(accounts(1), (0, 5))]))
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+    }
+
+    #[test]
+    fn spender_batch_transfers_under_several_approvals() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(0), &["silver".into()], &[10], None);
+        mt.mt_approve(vec!["silver".into()], vec![U128(4)], accounts(1), None);
+        as_account(accounts(1));
+        mt.mt_batch_transfer(
+            accounts(2),
+            vec!["gold".into(), "silver".into()],
+            vec![U128(2), U128(4)],
+            Some(vec![Some((accounts(0), 0)), Some((accounts(0), 1))]),
+            None,
+        );
+        assert_eq!(mt.mt_balance_of(accounts(2), "silver".into()), U128(4));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"charlie","token_ids":["gold","silver"],"amounts":["2","4"],"authorized_id":"bob","approval_ids":[0,1]}]}"#
+        );
+    }
+
+    #[test]
+    fn owner_transfer_caps_approvals_at_balance() {
+        let mut mt = setup();
+        let gold: TokenId = "gold".into();
+        mt.mt_approve(vec![gold.clone()], vec![U128(90)], accounts(3), None);
+        // The approval naming alice herself is ignored, she transfers as the owner.
+        mt.mt_transfer(
+            accounts(2),
+            gold.clone(),
+            U128(20),
+            Some((accounts(0), 7)),
+            None,
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(3), Some(1)),
+            80
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(1), Some(0)),
+            5
+        );
+    }
+
+    #[test]
+    fn burn_caps_approvals_at_balance() {
+        let mut mt = setup();
+        let gold: TokenId = "gold".into();
+        mt.mt_approve(vec![gold.clone()], vec![U128(2)], accounts(2), None);
+        mt.internal_burn(&accounts(0), std::slice::from_ref(&gold), &[3], None);
+        assert_eq!(mt.mt_balance_of(accounts(0), gold.clone()), U128(97));
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(1), Some(0)),
+            5
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(2), Some(1)),
+            2
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold"]}]}"#
+        );
+
+        mt.internal_burn(&accounts(0), std::slice::from_ref(&gold), &[93], None);
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(1), Some(0)),
+            4
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(2), Some(1)),
+            2
+        );
+
+        mt.internal_burn(&accounts(0), std::slice::from_ref(&gold), &[4], None);
+        assert!(mt
+            .approvals_by_id
+            .as_ref()
+            .unwrap()
+            .get(&(accounts(0), gold))
+            .is_none());
+    }
+
+    #[test]
+    fn spender_transfers_up_to_the_approved_amount() {
+        let mut mt = setup();
+        as_account(accounts(1));
+        let receipt = mt.mt_transfer_up_to(
+            accounts(2),
+            "gold".into(),
+            U128(10),
+            Some((accounts(0), 0)),
+            None,
+        );
+        assert_eq!(receipt.amounts, [U128(5)]);
+        assert_eq!(receipt.old_owner_id, accounts(0));
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(95));
+    }
+
+    /// Like `setup`, but bob's approval is for 50 gold under approval ID 7.
+    fn approved_as_seven() -> MultiToken {
+        let mut mt = setup();
+        mt.next_approval_id = 7;
+        mt.mt_approve(vec!["gold".into()], vec![U128(50)], accounts(1), None);
+        as_account(accounts(1));
+        mt
+    }
+
+    #[test]
+    fn spender_transfers_under_the_matching_approval_id() {
+        let mut mt = approved_as_seven();
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(30),
+            Some((accounts(0), 7)),
+            None,
+        );
+        assert_eq!(mt.mt_balance_of(accounts(2), "gold".into()), U128(30));
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), Some(7)),
+            20
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid approval ID")]
+    fn spender_cannot_transfer_under_another_approval_id() {
+        let mut mt = approved_as_seven();
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(30),
+            Some((accounts(0), 8)),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer amount exceeds the approved amount")]
+    fn spender_cannot_exceed_approval() {
+        let mut mt = setup();
+        as_account(accounts(1));
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(6),
+            Some((accounts(0), 0)),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender is not approved")]
+    fn revoke_all_removes_approvals() {
+        let mut mt = setup();
+        mt.mt_revoke_all(vec!["gold".into()]);
+        assert!(!mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(1)],
+            None
+        ));
+
+        as_account(accounts(1));
+        mt.mt_transfer(
+            accounts(2),
+            "gold".into(),
+            U128(1),
+            Some((accounts(0), 0)),
+            None,
+        );
+    }
+
+    #[test]
+    fn revoke_existing_approval() {
+        let mut mt = setup();
+        assert_eq!(
+            mt.mt_revoke(vec!["gold".into()], vec![U128(5)], accounts(1)),
+            vec![true]
+        );
+        assert!(!mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(1)],
+            None
+        ));
+        assert!(mt
+            .approvals_by_id
+            .as_ref()
+            .unwrap()
+            .get(&(accounts(0), "gold".into()))
+            .is_none());
+    }
+
+    #[test]
+    fn revoke_missing_approval() {
+        let mut mt = setup();
+        as_account(accounts(0));
+        assert_eq!(
+            mt.mt_revoke(vec!["gold".into()], vec![U128(5)], accounts(2)),
+            vec![false]
+        );
+        assert!(near_sdk::test_utils::get_logs().is_empty());
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), Some(0)),
+            5
+        );
+    }
+
+    #[test]
+    fn restore_only_touches_same_approval() {
+        let mut mt = setup();
+        let gold = "gold".into();
+        mt.internal_spend_approval(&accounts(0), &gold, &accounts(1), 0, 5);
+        mt.internal_restore_approval(&accounts(0), &gold, &accounts(1), 0, 4);
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(1), Some(0)),
+            4
+        );
+
+        mt.mt_approve(vec![gold.clone()], vec![U128(1)], accounts(1), None);
+        mt.internal_restore_approval(&accounts(0), &gold, &accounts(1), 0, 4);
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &gold, &accounts(1), Some(1)),
+            1
+        );
+    }
+}
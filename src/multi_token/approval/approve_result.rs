@@ -0,0 +1,77 @@
+//! Conventions for the `String` returned by [`mt_on_approve`].
+//!
+//! The content is contract defined, but an MT contract resolving the approval call needs
+//! to know whether the receiver wants to keep the approval. Receivers following this
+//! convention return one of:
+//! * `"ok"`: the approval was accepted.
+//! * `"reject"`: the approval should be revoked.
+//! * a JSON document. It counts as a rejection if it is an object whose `"status"`
+//!   field is `"reject"`, and as accepted otherwise.
+//!
+//! Anything else is reported as [`ApproveOutcome::Unrecognized`] and, like a payload,
+//! keeps the approval: the owner granted it and only an explicit rejection undoes that.
+//!
+//! [`mt_on_approve`]: super::MultiTokenApprovalReceiver::mt_on_approve
+
+/// Result of [`parse_approve_result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApproveOutcome {
+    Ok,
+    Reject,
+    Payload(serde_json::Value),
+    Unrecognized(String),
+}
+
+impl ApproveOutcome {
+    /// Whether the MT contract should keep the approval it granted.
+    pub fn keeps_approval(&self) -> bool {
+        !matches!(self, ApproveOutcome::Reject)
+    }
+}
+
+/// Interprets the string returned by `mt_on_approve` following the module conventions.
+pub fn parse_approve_result(s: &str) -> ApproveOutcome {
+    match s.trim() {
+        "ok" => ApproveOutcome::Ok,
+        "reject" => ApproveOutcome::Reject,
+        other => match serde_json::from_str::<serde_json::Value>(other) {
+            Ok(value) if value.get("status").and_then(|s| s.as_str()) == Some("reject") => {
+                ApproveOutcome::Reject
+            }
+            Ok(value) => ApproveOutcome::Payload(value),
+            Err(_) => ApproveOutcome::Unrecognized(s.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_plain_results() {
+        assert_eq!(parse_approve_result("ok"), ApproveOutcome::Ok);
+        assert_eq!(parse_approve_result(" reject\n"), ApproveOutcome::Reject);
+        assert!(parse_approve_result("ok").keeps_approval());
+        assert!(!parse_approve_result("reject").keeps_approval());
+    }
+
+    #[test]
+    fn parse_json_results() {
+        assert_eq!(
+            parse_approve_result(r#"{"status":"reject","reason":"not listed"}"#),
+            ApproveOutcome::Reject
+        );
+        let outcome = parse_approve_result(r#"{"listing_id":7}"#);
+        assert_eq!(outcome, ApproveOutcome::Payload(json!({ "listing_id": 7 })));
+        assert!(outcome.keeps_approval());
+    }
+
+    #[test]
+    fn parse_unrecognized_result() {
+        let outcome = parse_approve_result("listed!");
+        assert_eq!(outcome, ApproveOutcome::Unrecognized("listed!".to_string()));
+        assert!(outcome.keeps_approval());
+    }
+}
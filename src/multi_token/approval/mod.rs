@@ -1,24 +1,40 @@
 mod approval_impl;
 mod approval_receiver;
+pub mod approve_result;
 
-pub use approval_impl::*;
 pub use approval_receiver::*;
+pub use approve_result::{parse_approve_result, ApproveOutcome};
 
 use crate::multi_token::token::TokenId;
-use near_sdk::AccountId;
-use near_sdk::Promise;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, AccountId, Balance, PromiseOrValue};
+use std::collections::HashMap;
 
-/// Trait used when it's desired to have a non-fungible token that has a
+/// Approved AccountId -> (approval ID, approved amount) for one owner's token.
+pub type Approvals = HashMap<AccountId, (u64, Balance)>;
+
+/// Trait used when it's desired to have a multi token that has a
 /// traditional escrow or approval system. This allows Alice to allow Bob
-/// to take only the token with the unique identifier "19" but not others.
-/// It should be noted that in the [core non-fungible token standard] there
+/// to take up to 5 of the token with the identifier "gold" but no more, and
+/// none of her other tokens. Because multi tokens are semi-fungible, every
+/// approval is scoped to an amount as well as an account.
+/// It should be noted that in the [core multi token standard] there
 /// is a method to do "transfer and call" which may be preferred over using
-/// an approval management standard in certain use cases.
+/// an [approval management standard] in certain use cases.
+///
+/// Approval IDs: each approval is stored with an `approval_id` that the contract
+/// takes from a monotonically increasing counter, assigning a fresh ID on every
+/// `mt_approve`, including re-approvals of the same account. An ID is therefore
+/// never reused, and a spender passing an old ID (see `approval_ids` in
+/// [`mt_on_approve`](crate::multi_token::approval::MultiTokenApprovalReceiver::mt_on_approve))
+/// can't act on an approval the owner has since revoked and granted anew. IDs stay
+/// below 2^53 so that they are representable as JSON numbers.
 ///
-/// [approval management standard]: https://nomicon.io/Standards/MultiToken/ApprovalManagement.html
-/// [core non-fungible token standard]: https://nomicon.io/Standards/MultiToken/Core.html
+/// [approval management standard]: https://nomicon.io/Standards/Tokens/MultiToken/ApprovalManagement
+/// [core multi token standard]: https://nomicon.io/Standards/Tokens/MultiToken/Core
+#[ext_contract(ext_mt_approval)]
 pub trait MultiTokenApproval {
-    /// Add an approved account for a specific token.
+    /// Approve an account to transfer up to `amounts[i]` of each `token_ids[i]`.
     ///
     /// Requirements
     /// * Caller of the method must attach a deposit of at least 1 yoctoⓃ for
@@ -26,28 +42,34 @@ pub trait MultiTokenApproval {
     /// * Contract MAY require caller to attach larger deposit, to cover cost of
     ///   storing approver data
     /// * Contract MUST panic if called by someone other than token owner
+    /// * Contract MUST panic if `token_ids` is empty
+    /// * Contract MUST panic if `token_ids` and `amounts` differ in length
     /// * Contract MUST panic if addition would cause `mt_revoke_all` to exceed
     ///   single-block gas limit
     /// * Contract MUST increment approval ID even if re-approving an account
     /// * If successfully approved or if had already been approved, and if `msg` is
     ///   present, contract MUST call `mt_on_approve` on `account_id`. See
-    ///   `mt_on_approve` description below for details.
+    ///   `mt_on_approve` description for details.
     ///
     /// Arguments:
-    /// * `token_id`: the token for which to add an approval
+    /// * `token_ids`: the tokens for which to add an approval
+    /// * `amounts`: the amount of each token `account_id` may transfer
     /// * `account_id`: the account to add to `approvals`
     /// * `msg`: optional string to be passed to `mt_on_approve`
     ///
-    /// Returns void, if no `msg` given. Otherwise, returns promise call to
-    /// `mt_on_approve`, which can resolve with whatever it wants.
+    /// Returns the assigned approval IDs, one per token in the order of `token_ids`, if
+    /// no `msg` given. Otherwise, returns promise call to `mt_on_approve`, which can
+    /// resolve with whatever it wants.
     fn mt_approve(
         &mut self,
-        token_id: TokenId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
         account_id: AccountId,
         msg: Option<String>,
-    ) -> Option<Promise>;
+    ) -> PromiseOrValue<Vec<u64>>;
 
-    /// Revoke an approved account for a specific token.
+    /// Reduce the approved amount of an account for each of `token_ids` by the
+    /// matching entry of `amounts`, removing the approval once nothing is left.
     ///
     /// Requirements
     /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security
@@ -57,11 +79,21 @@ pub trait MultiTokenApproval {
     /// * Contract MUST panic if called by someone other than token owner
     ///
     /// Arguments:
-    /// * `token_id`: the token for which to revoke an approval
-    /// * `account_id`: the account to remove from `approvals`
-    fn mt_revoke(&mut self, token_id: TokenId, account_id: AccountId);
+    /// * `token_ids`: the tokens for which to revoke an approval
+    /// * `amounts`: the amount of each token to revoke
+    /// * `account_id`: the account whose approvals to reduce
+    ///
+    /// Returns, for each entry of `token_ids`, `true` if `account_id` was approved
+    /// and the approval has been reduced, `false` if there was nothing to revoke.
+    /// Revoke events, if any, should only be emitted for `true` entries.
+    fn mt_revoke(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        account_id: AccountId,
+    ) -> Vec<bool>;
 
-    /// Revoke all approved accounts for a specific token.
+    /// Revoke all approved accounts for each of `token_ids`.
     ///
     /// Requirements
     /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security
@@ -71,24 +103,33 @@ pub trait MultiTokenApproval {
     /// * Contract MUST panic if called by someone other than token owner
     ///
     /// Arguments:
-    /// * `token_id`: the token with approvals to revoke
-    fn mt_revoke_all(&mut self, token_id: TokenId);
+    /// * `token_ids`: the tokens with approvals to revoke
+    fn mt_revoke_all(&mut self, token_ids: Vec<TokenId>);
 
-    /// Check if a token is approved for transfer by a given account, optionally
-    /// checking an approval_id
+    /// Check if tokens are approved for transfer by a given account, optionally
+    /// checking approval IDs. Approvals are kept per owner, so the holder whose
+    /// approvals to check must be named.
     ///
     /// Arguments:
-    /// * `token_id`: the token for which to revoke an approval
+    /// * `token_ids`: the tokens to check
+    /// * `owner_id`: the holder who gave the approvals
     /// * `approved_account_id`: the account to check the existence of in `approvals`
-    /// * `approval_id`: an optional approval ID to check against current approval ID for given account
+    /// * `amounts`: the amount of each token `approved_account_id` must be approved for.
+    ///   If empty, or for an amount of 0, any nonzero approval counts
+    /// * `approval_ids`: optional approval IDs to check against the current approval
+    ///   ID for given account, one per token
     ///
     /// Returns:
-    /// if `approval_id` given, `true` if `approved_account_id` is approved with given `approval_id`
-    /// otherwise, `true` if `approved_account_id` is in list of approved accounts
+    /// `true` only if, for every token, `approved_account_id` is approved for at
+    /// least the given amount and, if `approval_ids` given, with the given approval ID.
+    /// Panics if `token_ids` is empty
+    #[must_use]
     fn mt_is_approved(
         &self,
-        token_id: TokenId,
+        token_ids: Vec<TokenId>,
+        owner_id: AccountId,
         approved_account_id: AccountId,
-        approval_id: Option<u64>,
+        amounts: Vec<U128>,
+        approval_ids: Option<Vec<u64>>,
     ) -> bool;
 }
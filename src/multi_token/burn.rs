@@ -0,0 +1,240 @@
+use crate::multi_token::core::{batch_owner, check_amounts, MultiToken};
+use crate::multi_token::error::{unwrap_or_abort, MtError};
+use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, AccountId, Balance};
+use std::collections::BTreeMap;
+
+/// Most token ids a single batch burn may list, so that burning them fits in one call.
+pub const MAX_BURN_BATCH_SIZE: usize = 64;
+
+/// Lets holders burn their own tokens, several ids at once.
+pub trait MultiTokenBurn {
+    /// Burn `amounts[i]` of each `token_ids[i]` held by the predecessor and emit a single
+    /// `MtBurn` event for the whole batch.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security purposes
+    /// * Contract MUST panic if `token_ids` is empty, lists more than
+    ///   [`MAX_BURN_BATCH_SIZE`] ids, or differs in length from `amounts`
+    /// * Contract MUST panic if any amount is 0 or more than the caller holds, and burns
+    ///   nothing in that case
+    ///
+    /// Arguments:
+    /// * `token_ids`: the tokens to burn
+    /// * `amounts`: the amount of each token to burn
+    /// * `memo`: for use cases that may benefit from indexing or providing information
+    ///   for a burn
+    fn mt_batch_burn(&mut self, token_ids: Vec<TokenId>, amounts: Vec<U128>, memo: Option<String>);
+}
+
+impl MultiToken {
+    /// Burns `amounts[i]` of each `token_ids[i]` on behalf of `sender_id`: the owner, or
+    /// with `approvals` an account the owner approved for every id, whose approvals are
+    /// spent like a transfer's. Every id is checked before any is burned, so a batch either
+    /// burns completely or panics without changing state. The `MtBurn` event names
+    /// `sender_id` as `authorized_id` unless it is the owner. Returns the owner the tokens
+    /// were burned from. Access control is up to the contract.
+    pub fn internal_batch_burn(
+        &mut self,
+        sender_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: &[Option<(AccountId, u64)>],
+        memo: Option<String>,
+    ) -> AccountId {
+        let owner_id = unwrap_or_abort(
+            self.internal_check_batch_burn(sender_id, token_ids, amounts, approvals),
+        );
+        if &owner_id != sender_id {
+            for (i, (token_id, &amount)) in token_ids.iter().zip(amounts).enumerate() {
+                let (_, approval_id) = approvals[i].as_ref().unwrap();
+                self.internal_spend_approval(&owner_id, token_id, sender_id, *approval_id, amount);
+            }
+        }
+        self.internal_burn_by(sender_id, &owner_id, token_ids, amounts, memo);
+        owner_id
+    }
+
+    fn internal_check_batch_burn(
+        &self,
+        sender_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: &[Option<(AccountId, u64)>],
+    ) -> Result<AccountId, MtError> {
+        if token_ids.is_empty() {
+            return Err(MtError::NoTokenIds);
+        }
+        if token_ids.len() > MAX_BURN_BATCH_SIZE {
+            return Err(MtError::BatchTooLarge);
+        }
+        check_amounts(token_ids, amounts)?;
+        if !approvals.is_empty() && approvals.len() != token_ids.len() {
+            return Err(MtError::ApprovalsLengthMismatch);
+        }
+        let owner_id = batch_owner(sender_id, approvals)?;
+        // Summed per id, so an id listed more than once is checked for its whole amount.
+        let mut totals: BTreeMap<&TokenId, Balance> = BTreeMap::new();
+        for (i, (token_id, &amount)) in token_ids.iter().zip(amounts).enumerate() {
+            let total = totals.entry(token_id).or_default();
+            *total = total
+                .checked_add(amount)
+                .ok_or(MtError::InsufficientBalance)?;
+            if &owner_id != sender_id {
+                let (_, approval_id) = approvals[i].as_ref().unwrap();
+                self.internal_check_approval(&owner_id, token_id, sender_id, *approval_id, *total)?;
+            }
+        }
+        for (token_id, total) in totals {
+            let balance = self.internal_balance_of(&owner_id, token_id);
+            if balance < total {
+                return Err(self.internal_insufficient_balance(&owner_id, token_id, balance, total));
+            }
+        }
+        Ok(owner_id)
+    }
+}
+
+impl MultiTokenBurn for MultiToken {
+    fn mt_batch_burn(&mut self, token_ids: Vec<TokenId>, amounts: Vec<U128>, memo: Option<String>) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(Balance::from).collect();
+        self.internal_batch_burn(&sender_id, &token_ids, &amounts, &[], memo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::approval::MultiTokenApproval;
+    use crate::multi_token::core::MultiTokenCore;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Alice holds 100 gold and 10 silver, and approved bob for 30 gold.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_approvals();
+        mt.internal_mint(
+            &accounts(0),
+            &["gold".into(), "silver".into()],
+            &[100, 10],
+            None,
+        );
+        mt.mt_approve(vec!["gold".into()], vec![U128(30)], accounts(1), None);
+        mt
+    }
+
+    fn balances(mt: &MultiToken) -> Vec<U128> {
+        mt.mt_batch_balance_of(accounts(0), vec!["gold".into(), "silver".into()])
+    }
+
+    #[test]
+    fn batch_burn_burns_every_id() {
+        let mut mt = setup();
+        mt.mt_batch_burn(
+            vec!["gold".into(), "silver".into()],
+            vec![U128(40), U128(10)],
+            Some("melt".into()),
+        );
+        assert_eq!(balances(&mt), [U128(60), U128(0)]);
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(60)));
+        assert_eq!(mt.mt_supply("silver".into()), Some(U128(0)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold","silver"],"memo":"melt"}]}"#
+        );
+    }
+
+    #[test]
+    fn failing_batch_burns_nothing() {
+        let mut mt = setup();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            mt.mt_batch_burn(
+                vec!["gold".into(), "silver".into()],
+                vec![U128(40), U128(11)],
+                None,
+            )
+        }));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(message, "The account doesn't have enough balance");
+        assert_eq!(balances(&mt), [U128(100), U128(10)]);
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+    }
+
+    #[test]
+    fn approved_account_can_burn() {
+        let mut mt = setup();
+        let owner_id = mt.internal_batch_burn(
+            &accounts(1),
+            &["gold".into()],
+            &[20],
+            &[Some((accounts(0), 0))],
+            None,
+        );
+        assert_eq!(owner_id, accounts(0));
+        assert_eq!(balances(&mt), [U128(80), U128(10)]);
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), Some(0)),
+            10
+        );
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn burn_event_names_only_an_approved_burner() {
+        let mut mt = setup();
+        mt.internal_batch_burn(&accounts(0), &["gold".into()], &[5], &[], None);
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold"]}]}"#
+        );
+        // The predecessor is still alice, the event names bob who burned on her behalf.
+        mt.internal_batch_burn(
+            &accounts(1),
+            &["gold".into()],
+            &[5],
+            &[Some((accounts(0), 0))],
+            None,
+        );
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold"],"authorized_id":"bob"}]}"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer amount exceeds the approved amount")]
+    fn approved_burn_is_limited_to_the_approval() {
+        let mut mt = setup();
+        mt.internal_batch_burn(
+            &accounts(1),
+            &["gold".into(), "gold".into()],
+            &[20, 20],
+            &[Some((accounts(0), 0)), Some((accounts(0), 0))],
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "token_ids and amounts must have the same length")]
+    fn batch_burn_requires_equal_lengths() {
+        setup().mt_batch_burn(vec!["gold".into()], vec![U128(1), U128(1)], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch exceeds the maximum size")]
+    fn batch_burn_is_capped() {
+        let token_ids = vec!["gold".into(); MAX_BURN_BATCH_SIZE + 1];
+        let amounts = vec![U128(1); MAX_BURN_BATCH_SIZE + 1];
+        setup().mt_batch_burn(token_ids, amounts, None);
+    }
+}
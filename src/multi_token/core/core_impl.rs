@@ -0,0 +1,3340 @@
+use crate::multi_token::approval::Approvals;
+use crate::multi_token::core::receiver::ext_mt_receiver;
+use crate::multi_token::core::resolver::{ext_mt_resolver, MultiTokenResolver};
+use crate::multi_token::core::MultiTokenCore;
+use crate::multi_token::error::{abort, unwrap_or_abort, MtError};
+#[cfg(feature = "events")]
+use crate::multi_token::events::{
+    MtBurn, MtBurnOwned, MtMint, MtMintOwned, MtTransfer, MtTransferOwned,
+};
+use crate::multi_token::metadata::{MetadataOnRemint, TokenMetadata};
+use crate::multi_token::storage_impl::StorageAccount;
+use crate::multi_token::token::{Token, TokenId, TokenKind};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    assert_one_yocto, env, require, AccountId, Balance, Gas, IntoStorageKey, Promise,
+    PromiseOrValue, PromiseResult, StorageUsage,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Gas reserved for the `mt_resolve_transfer` callback.
+pub const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
+/// Gas forwarded to the receiver's `mt_on_transfer`.
+pub const GAS_FOR_MT_ON_TRANSFER: Gas = Gas(25_000_000_000_000);
+/// Gas the transfer itself and scheduling of the promise chain needs on top of the two calls.
+pub const MIN_CALLBACK_OVERHEAD: Gas = Gas(5_000_000_000_000);
+
+/// Base cost of a batch transfer call, independent of its size.
+const GAS_ESTIMATE_BATCH_BASE: Gas = Gas(5_000_000_000_000);
+/// Cost of moving balances and logging a single id within a batch.
+const GAS_ESTIMATE_PER_TOKEN: Gas = Gas(2_000_000_000_000);
+/// Additional cost per id of checking and consuming an approval.
+const GAS_ESTIMATE_PER_APPROVAL: Gas = Gas(1_000_000_000_000);
+
+/// Heuristic estimate of the gas `mt_batch_transfer` needs for `token_ids`, for wallets to
+/// size their gas limit before submitting. This is an estimate, not a guarantee: actual
+/// usage depends on id lengths, storage layout and the runtime's fee config.
+///
+/// Contracts can expose it as the `mt_batch_transfer_gas_estimate` view.
+pub fn mt_batch_transfer_gas_estimate(token_ids: &[TokenId], has_approvals: bool) -> u64 {
+    let per_token = if has_approvals {
+        GAS_ESTIMATE_PER_TOKEN.0 + GAS_ESTIMATE_PER_APPROVAL.0
+    } else {
+        GAS_ESTIMATE_PER_TOKEN.0
+    };
+    GAS_ESTIMATE_BATCH_BASE
+        .0
+        .saturating_add(per_token.saturating_mul(token_ids.len() as u64))
+}
+
+/// Gas split of the `mt_on_transfer` + `mt_resolve_transfer` chain scheduled by
+/// [`MultiToken::internal_transfer_call`]. The default reserves
+/// [`GAS_FOR_MT_ON_TRANSFER`] and [`GAS_FOR_RESOLVE_TRANSFER`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferCallGas {
+    /// Gas reserved for the receiver's `mt_on_transfer`, which also gets any gas left over.
+    pub mt_on_transfer: Gas,
+    /// Gas reserved for the `mt_resolve_transfer` callback.
+    pub resolve_transfer: Gas,
+}
+
+impl Default for TransferCallGas {
+    fn default() -> Self {
+        Self {
+            mt_on_transfer: GAS_FOR_MT_ON_TRANSFER,
+            resolve_transfer: GAS_FOR_RESOLVE_TRANSFER,
+        }
+    }
+}
+
+impl TransferCallGas {
+    /// Asserts there is enough prepaid gas for both calls plus [`MIN_CALLBACK_OVERHEAD`].
+    pub fn assert_enough_prepaid_gas(&self) {
+        require!(
+            env::prepaid_gas()
+                > self.mt_on_transfer + self.resolve_transfer + MIN_CALLBACK_OVERHEAD,
+            "More gas is required"
+        );
+    }
+}
+
+/// What a transfer actually moved, returned by [`MultiToken::mt_transfer_up_to`] so composing
+/// contracts can react to the exact amounts when they differ from the requested ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferReceipt {
+    pub token_ids: Vec<TokenId>,
+    /// Amount moved of each of `token_ids`.
+    pub amounts: Vec<U128>,
+    /// The owner the tokens came from, which differs from the caller for approved transfers.
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+}
+
+/// Asserts there is enough prepaid gas for the `mt_on_transfer` + `mt_resolve_transfer`
+/// chain to complete. Call this before moving any tokens in `mt_transfer_call`, so that
+/// an underfunded call is rejected up front instead of leaving tokens stuck with a
+/// receiver whose resolve callback never ran.
+pub fn assert_enough_gas_for_transfer_call() {
+    TransferCallGas::default().assert_enough_prepaid_gas()
+}
+
+/// Asserts the predecessor is this contract itself, as callbacks such as
+/// `mt_resolve_transfer` require. Contracts implementing [`MultiTokenResolver`] by hand
+/// should call it first thing.
+pub fn assert_self() {
+    require!(
+        env::predecessor_account_id() == env::current_account_id(),
+        "Method is private"
+    );
+}
+
+#[cfg(test)]
+thread_local! {
+    static STORAGE_READS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns and resets the number of balance and token records read on this thread. Only
+/// unit tests count reads, to pin down how often hot paths go to storage.
+#[cfg(test)]
+pub(crate) fn take_storage_reads() -> u64 {
+    STORAGE_READS.with(|reads| reads.replace(0))
+}
+
+fn count_storage_read() {
+    #[cfg(test)]
+    STORAGE_READS.with(|reads| reads.set(reads.get() + 1));
+}
+
+/// (snapshot ID, balance at that snapshot) pairs of one balance, oldest first, see
+/// [`MultiToken::take_snapshot`].
+pub type Checkpoints = Vec<(u64, Balance)>;
+
+/// What [`internal_batch_transfer`](MultiToken::internal_batch_transfer) does with a batch
+/// listing the same token more than once.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateTokenIds {
+    /// Move every entry as given; the event lists each of them.
+    #[default]
+    Allow,
+    /// Panic, so clients have to send each id once.
+    Reject,
+    /// Merge the entries of an id into one with the summed amount, in the place of the
+    /// first, so the event has one entry per id.
+    Coalesce,
+}
+
+/// Implementation of a MultiToken standard.
+/// Allows to include NEP-245 compatible tokens to any contract.
+/// There are next traits that any contract may implement:
+///     - MultiTokenCore -- interface with mt_transfer methods. MultiToken provides methods for it.
+///     - MultiTokenResolver -- callback that finishes `mt_transfer_call`. MultiToken provides methods for it.
+///     - MultiTokenApproval -- interface for amount-scoped approvals. MultiToken provides methods for it.
+///     - MultiTokenEnumeration -- interface for paging through tokens. MultiToken provides methods for it.
+///
+/// All collections live under the single `prefix` given to [`MultiToken::new`], so several
+/// instances can coexist in one contract as long as their prefixes differ.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MultiToken {
+    /// (AccountId, TokenId) -> Account balance of that token.
+    pub balances: LookupMap<(AccountId, TokenId), Balance>,
+
+    /// TokenId -> Total supply of that token.
+    pub total_supply: LookupMap<TokenId, Balance>,
+
+    /// TokenId -> Accounts holding a non-zero balance of that token.
+    pub owners_by_id: LookupMap<TokenId, UnorderedSet<AccountId>>,
+
+    /// TokenId -> Kind recorded at first mint, if the registry is enabled.
+    pub id_kinds: Option<LookupMap<TokenId, TokenKind>>,
+
+    /// TokenId -> Token metadata, if the metadata extension is enabled.
+    pub token_metadata_by_id: Option<LookupMap<TokenId, TokenMetadata>>,
+
+    /// What minting more of an id that already has metadata does with new metadata.
+    pub metadata_on_remint: MetadataOnRemint,
+
+    /// (Owner AccountId, TokenId) -> Approved AccountId -> (approval ID, approved amount),
+    /// if the approval extension is enabled. Entries go away once the owner's balance of
+    /// the token reaches 0, and their storage is paid for by the owner.
+    pub approvals_by_id: Option<LookupMap<(AccountId, TokenId), Approvals>>,
+
+    /// Approval ID handed out by the next `mt_approve`. Never decreases.
+    pub next_approval_id: u64,
+
+    /// Every TokenId ever minted, if the enumeration extension is enabled. With
+    /// `remove_on_zero_supply`, only those with a non-zero supply.
+    pub all_token_ids: Option<UnorderedSet<TokenId>>,
+
+    /// AccountId -> Tokens it holds a non-zero balance of, if the enumeration extension
+    /// is enabled.
+    pub tokens_per_owner: Option<LookupMap<AccountId, UnorderedSet<TokenId>>>,
+
+    /// AccountId -> NEAR deposited for storage and bytes used, if storage management is
+    /// enabled.
+    pub storage_accounts: Option<LookupMap<AccountId, StorageAccount>>,
+
+    /// TokenId -> Block timestamp of its last mint or transfer, if transfer timestamps are
+    /// tracked.
+    pub last_transfer_at: Option<LookupMap<TokenId, u64>>,
+
+    /// TokenId -> Total amount of it transferred so far, if transfer volume is tracked.
+    pub transfer_volume: Option<LookupMap<TokenId, Balance>>,
+
+    /// (AccountId, TokenId) -> Amount locked in escrow, if escrow locks are enabled. Locked
+    /// amounts are kept out of `balances`, but still count towards the total supply.
+    pub locked_balances: Option<LookupMap<(AccountId, TokenId), Balance>>,
+
+    /// (AccountId, TokenId) -> (snapshot ID, balance at that snapshot) checkpoints, oldest
+    /// first, if balance snapshots are enabled.
+    pub balance_checkpoints: Option<LookupMap<(AccountId, TokenId), Checkpoints>>,
+
+    /// ID of the latest snapshot taken, 0 before the first one.
+    pub snapshot_id: u64,
+
+    /// Account allowed to pause and unpause the contract, if the pause switch is enabled.
+    pub pause_admin: Option<AccountId>,
+
+    /// Whether transfers and approvals are blocked, see [`MultiToken::pause`].
+    pub paused: bool,
+
+    /// Relayers allowed to transfer on behalf of users, if relayed transfers are enabled,
+    /// see [`MultiToken::mt_relayed_transfer`].
+    pub trusted_relayers: Option<LookupSet<AccountId>>,
+
+    /// Receivers `mt_transfer_call` may call, if strict receivers are enabled, see
+    /// [`MultiToken::with_receiver_allowlist`].
+    pub allowed_receivers: Option<LookupSet<AccountId>>,
+
+    /// Whether an id burned down to a total supply of 0 is forgotten, see
+    /// [`MultiToken::with_remove_on_zero_supply`].
+    pub remove_on_zero_supply: bool,
+
+    /// How batch transfers treat a token listed twice, see [`DuplicateTokenIds`].
+    pub duplicate_token_ids: DuplicateTokenIds,
+
+    /// The storage size in bytes for one registered account.
+    pub account_storage_usage: StorageUsage,
+
+    prefix: Vec<u8>,
+}
+
+impl MultiToken {
+    /// Creates an instance storing its collections under `prefix`. Panics with
+    /// `"Already initialized"` if an instance was already created under `prefix`, since
+    /// that would reset its approval IDs, pause state and settings.
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        require!(
+            !env::storage_has_key(&initialized_key(&prefix)),
+            "Already initialized"
+        );
+        Self::migrate(prefix)
+    }
+
+    /// Like [`new`](MultiToken::new), but also for a `prefix` that is already in use, for
+    /// state migrations that deliberately rebuild the instance. The new instance sees the
+    /// balances, supplies and other collections stored under `prefix`, but starts over with
+    /// the plain fields, such as `next_approval_id`, which the migration has to carry over.
+    pub fn migrate<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        env::storage_write(&initialized_key(&prefix), &[]);
+        Self {
+            balances: LookupMap::new([prefix.as_slice(), b"b"].concat()),
+            total_supply: LookupMap::new([prefix.as_slice(), b"s"].concat()),
+            owners_by_id: LookupMap::new([prefix.as_slice(), b"o"].concat()),
+            id_kinds: None,
+            token_metadata_by_id: None,
+            metadata_on_remint: MetadataOnRemint::default(),
+            approvals_by_id: None,
+            next_approval_id: 0,
+            all_token_ids: None,
+            tokens_per_owner: None,
+            storage_accounts: None,
+            last_transfer_at: None,
+            transfer_volume: None,
+            locked_balances: None,
+            balance_checkpoints: None,
+            snapshot_id: 0,
+            pause_admin: None,
+            paused: false,
+            trusted_relayers: None,
+            allowed_receivers: None,
+            remove_on_zero_supply: false,
+            duplicate_token_ids: DuplicateTokenIds::default(),
+            account_storage_usage: 0,
+            prefix,
+        }
+    }
+
+    /// Enables the [`TokenKind`] registry. Every id is then recorded as fungible or
+    /// non-fungible on first mint, and mints that would give a non-fungible id a supply
+    /// above 1 are rejected.
+    pub fn with_id_kinds(mut self) -> Self {
+        self.id_kinds = Some(LookupMap::new([self.prefix.as_slice(), b"k"].concat()));
+        self
+    }
+
+    /// Enables per-token metadata, returned by `mt_token` together with its
+    /// `decimals` and `symbol` display hints.
+    pub fn with_token_metadata(mut self) -> Self {
+        self.token_metadata_by_id = Some(LookupMap::new([self.prefix.as_slice(), b"m"].concat()));
+        self
+    }
+
+    /// Sets what [`internal_mint_with_metadata`](MultiToken::internal_mint_with_metadata)
+    /// does with metadata for ids that already have some. Defaults to
+    /// [`MetadataOnRemint::Reject`].
+    pub fn with_metadata_on_remint(mut self, policy: MetadataOnRemint) -> Self {
+        self.metadata_on_remint = policy;
+        self
+    }
+
+    /// Enables amount-scoped approvals, managed through `MultiTokenApproval`.
+    pub fn with_approvals(mut self) -> Self {
+        self.approvals_by_id = Some(LookupMap::new([self.prefix.as_slice(), b"a"].concat()));
+        self
+    }
+
+    /// Enables the indexes behind `MultiTokenEnumeration`. Only tokens minted and balances
+    /// changed afterwards are indexed, so enable it before the first mint.
+    pub fn with_enumeration(mut self) -> Self {
+        self.all_token_ids = Some(UnorderedSet::new([self.prefix.as_slice(), b"n"].concat()));
+        self.tokens_per_owner = Some(LookupMap::new([self.prefix.as_slice(), b"t"].concat()));
+        self
+    }
+
+    /// Enables NEP-145 storage management. Accounts then have to be registered through
+    /// `storage_deposit` before they can hold tokens, and pay for the storage their
+    /// balances use. Requires [`with_enumeration`](MultiToken::with_enumeration), which
+    /// is how `storage_unregister` finds an account's balances.
+    pub fn with_storage_management(mut self) -> Self {
+        require!(
+            self.tokens_per_owner.is_some(),
+            "Storage management requires enumeration"
+        );
+        self.storage_accounts = Some(LookupMap::new([self.prefix.as_slice(), b"r"].concat()));
+        self.measure_account_storage_usage();
+        self
+    }
+
+    /// Records the block timestamp of every mint and transfer, returned by `mt_token` as
+    /// `last_transfer_at`. Costs an extra write per token moved.
+    pub fn with_transfer_timestamps(mut self) -> Self {
+        self.last_transfer_at = Some(LookupMap::new([self.prefix.as_slice(), b"l"].concat()));
+        self
+    }
+
+    /// Adds up the amount of every transfer per token, returned by
+    /// [`mt_transfer_volume`](MultiToken::mt_transfer_volume). Mints, burns and the refunds
+    /// of `mt_transfer_call` don't count. Costs an extra write per token moved.
+    pub fn with_transfer_volume(mut self) -> Self {
+        self.transfer_volume = Some(LookupMap::new([self.prefix.as_slice(), b"v"].concat()));
+        self
+    }
+
+    /// Enables escrow locks, see [`internal_lock`](MultiToken::internal_lock).
+    pub fn with_locks(mut self) -> Self {
+        self.locked_balances = Some(LookupMap::new([self.prefix.as_slice(), b"e"].concat()));
+        self
+    }
+
+    /// Enables balance snapshots, see [`take_snapshot`](MultiToken::take_snapshot).
+    pub fn with_snapshots(mut self) -> Self {
+        self.balance_checkpoints = Some(LookupMap::new([self.prefix.as_slice(), b"c"].concat()));
+        self
+    }
+
+    /// Enables the contract-wide pause switch, operated by `admin_id` through
+    /// [`pause`](MultiToken::pause) and [`unpause`](MultiToken::unpause).
+    pub fn with_pause(mut self, admin_id: AccountId) -> Self {
+        self.pause_admin = Some(admin_id);
+        self
+    }
+
+    /// Enables relayed transfers, see [`mt_relayed_transfer`](MultiToken::mt_relayed_transfer).
+    pub fn with_trusted_relayers(mut self) -> Self {
+        self.trusted_relayers = Some(LookupSet::new([self.prefix.as_slice(), b"y"].concat()));
+        self
+    }
+
+    /// Makes `mt_transfer_call` and `mt_batch_transfer_call` panic up front for receivers
+    /// not added with [`internal_allow_receiver`](MultiToken::internal_allow_receiver), in
+    /// curated ecosystems where calling a contract without `mt_on_transfer` would only
+    /// waste the gas of a failed call and its refund. Plain transfers are not affected.
+    pub fn with_receiver_allowlist(mut self) -> Self {
+        self.allowed_receivers = Some(LookupSet::new([self.prefix.as_slice(), b"w"].concat()));
+        self
+    }
+
+    /// Forgets ids once burns bring their total supply to 0: `mt_supply` returns `None` for
+    /// them again and enumeration drops them, moving the last token into their place in
+    /// `mt_tokens`. By default they stay as a zero-supply entry, listed in `mt_tokens` and
+    /// counted by `mt_token_count`. Either way, minting the id again starts from 0.
+    pub fn with_remove_on_zero_supply(mut self) -> Self {
+        self.remove_on_zero_supply = true;
+        self
+    }
+
+    /// Sets what batch transfers do with an id listed more than once. Defaults to
+    /// [`DuplicateTokenIds::Allow`].
+    pub fn with_duplicate_token_ids(mut self, policy: DuplicateTokenIds) -> Self {
+        self.duplicate_token_ids = policy;
+        self
+    }
+
+    /// Stores `metadata` for `token_id`, replacing any previous metadata. Access control
+    /// is up to the contract.
+    pub fn internal_set_token_metadata(&mut self, token_id: &TokenId, metadata: &TokenMetadata) {
+        metadata.assert_valid();
+        match self.token_metadata_by_id.as_mut() {
+            Some(token_metadata_by_id) => {
+                token_metadata_by_id.insert(token_id, metadata);
+            }
+            None => require!(false, "Token metadata is not enabled"),
+        }
+    }
+
+    /// Replaces the stored metadata of `token_id` with `metadata`. Unless given, `issued_at`
+    /// is kept from the old metadata and `updated_at` is set to the current block timestamp,
+    /// both as stringified nanoseconds. Panics if `token_id` has no metadata yet. Access
+    /// control is up to the contract.
+    pub fn internal_update_token_metadata(&mut self, token_id: &TokenId, metadata: TokenMetadata) {
+        let old = self
+            .token_metadata_by_id
+            .as_ref()
+            .and_then(|token_metadata_by_id| token_metadata_by_id.get(token_id));
+        if old.is_none() {
+            require!(false, format!("Token {} has no metadata", token_id));
+        }
+        let metadata = TokenMetadata {
+            issued_at: metadata.issued_at.or(old.unwrap().issued_at),
+            updated_at: metadata
+                .updated_at
+                .or_else(|| Some(env::block_timestamp().to_string())),
+            ..metadata
+        };
+        self.internal_set_token_metadata(token_id, &metadata);
+    }
+
+    pub fn internal_balance_of(&self, account_id: &AccountId, token_id: &TokenId) -> Balance {
+        count_storage_read();
+        self.balances
+            .get(&(account_id.clone(), token_id.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn internal_deposit(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+    ) {
+        let initial_storage_usage = env::storage_usage();
+        self.internal_add_balance(account_id, token_id, amount);
+        self.internal_track_storage(account_id, initial_storage_usage);
+    }
+
+    fn internal_add_balance(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+    ) {
+        let balance = self.internal_balance_of(account_id, token_id);
+        self.internal_add_to_balance(account_id, token_id, balance, amount);
+    }
+
+    /// Adds `amount` to `balance`, the current balance of `account_id` for `token_id`.
+    fn internal_add_to_balance(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+        amount: Balance,
+    ) {
+        let supply = self.total_supply.get(token_id).unwrap_or(0);
+        match (balance.checked_add(amount), supply.checked_add(amount)) {
+            (Some(new_balance), Some(new_supply)) => {
+                if supply == 0 {
+                    if let Some(all_token_ids) = self.all_token_ids.as_mut() {
+                        all_token_ids.insert(token_id);
+                    }
+                }
+                self.internal_write_balance(account_id, token_id, balance, new_balance);
+                self.total_supply.insert(token_id, &new_supply);
+            }
+            (None, _) => abort(MtError::BalanceOverflow),
+            (_, None) => abort(MtError::TotalSupplyOverflow),
+        }
+    }
+
+    pub fn internal_withdraw(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+    ) {
+        let balance = self.internal_balance_of(account_id, token_id);
+        self.internal_withdraw_from_balance(account_id, token_id, balance, amount);
+    }
+
+    /// Subtracts `amount` from `balance`, the current balance of `account_id` for
+    /// `token_id`.
+    fn internal_withdraw_from_balance(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+        amount: Balance,
+    ) {
+        let initial_storage_usage = env::storage_usage();
+        let supply = self.total_supply.get(token_id).unwrap_or(0);
+        match (balance.checked_sub(amount), supply.checked_sub(amount)) {
+            (Some(new_balance), Some(new_supply)) => {
+                self.internal_write_balance(account_id, token_id, balance, new_balance);
+                self.total_supply.insert(token_id, &new_supply);
+            }
+            (None, _) => abort(MtError::InsufficientBalance),
+            (_, None) => abort(MtError::TotalSupplyUnderflow),
+        }
+        self.internal_track_storage(account_id, initial_storage_usage);
+    }
+
+    /// Replaces `balance`, the current balance of `account_id` for `token_id`, with
+    /// `new_balance` and keeps the owner indexes in step. Leaves the total supply and
+    /// storage accounting to the caller.
+    pub(crate) fn internal_write_balance(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+        new_balance: Balance,
+    ) {
+        self.internal_checkpoint(account_id, token_id, balance);
+        if balance == 0 && new_balance > 0 {
+            self.internal_update_owners(token_id, |owners| {
+                owners.insert(account_id);
+            });
+            self.internal_update_tokens_per_owner(account_id, |tokens| {
+                tokens.insert(token_id);
+            });
+        }
+        if balance > 0 && new_balance == 0 {
+            self.internal_update_owners(token_id, |owners| {
+                owners.remove(account_id);
+            });
+            self.internal_update_tokens_per_owner(account_id, |tokens| {
+                tokens.remove(token_id);
+            });
+        }
+        let key = (account_id.clone(), token_id.clone());
+        // Approvals go away with the owner's last unit of the token.
+        if new_balance == 0 {
+            if let Some(approvals_by_id) = self.approvals_by_id.as_mut() {
+                approvals_by_id.remove(&key);
+            }
+        }
+        // With storage management, empty rows are dropped so their storage is freed.
+        if new_balance == 0 && self.storage_accounts.is_some() {
+            self.balances.remove(&key);
+        } else {
+            self.balances.insert(&key, &new_balance);
+        }
+    }
+
+    /// Creates `amounts` of `token_ids` for `owner_id` and emits a single `MtMint` event.
+    /// Access control is up to the contract. With the [`TokenKind`] registry enabled, ids
+    /// minted for the first time are recorded as fungible.
+    pub fn internal_mint(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) {
+        self.internal_mint_kind(owner_id, token_ids, amounts, None, memo);
+    }
+
+    /// Same as [`internal_mint`](MultiToken::internal_mint), also storing `metadata[i]` for
+    /// `token_ids[i]`. For an id that already has metadata, `metadata_on_remint` decides
+    /// whether the mint is rejected, keeps the old metadata or replaces it. Unless given,
+    /// `issued_at` of new metadata is set to the current block timestamp, and replaced
+    /// metadata is stamped like [`internal_update_token_metadata`](MultiToken::internal_update_token_metadata).
+    /// Panics if token metadata is not enabled.
+    pub fn internal_mint_with_metadata(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        metadata: &[TokenMetadata],
+        memo: Option<String>,
+    ) {
+        require!(
+            token_ids.len() == metadata.len(),
+            "token_ids and metadata must have the same length"
+        );
+        let token_metadata_by_id = match self.token_metadata_by_id.as_ref() {
+            Some(token_metadata_by_id) => token_metadata_by_id,
+            None => {
+                require!(false, "Token metadata is not enabled");
+                return;
+            }
+        };
+        let updates: Vec<(&TokenId, &TokenMetadata, bool)> = token_ids
+            .iter()
+            .zip(metadata)
+            .filter_map(|(token_id, metadata)| {
+                if !token_metadata_by_id.contains_key(token_id) {
+                    return Some((token_id, metadata, false));
+                }
+                match self.metadata_on_remint {
+                    MetadataOnRemint::Reject => {
+                        require!(false, format!("Token {} already has metadata", token_id));
+                        None
+                    }
+                    MetadataOnRemint::Ignore => None,
+                    MetadataOnRemint::Overwrite => Some((token_id, metadata, true)),
+                }
+            })
+            .collect();
+        for (token_id, metadata, replaces) in updates {
+            if replaces {
+                self.internal_update_token_metadata(token_id, metadata.clone());
+            } else {
+                let metadata = TokenMetadata {
+                    issued_at: metadata
+                        .issued_at
+                        .clone()
+                        .or_else(|| Some(env::block_timestamp().to_string())),
+                    ..metadata.clone()
+                };
+                self.internal_set_token_metadata(token_id, &metadata);
+            }
+        }
+        self.internal_mint(owner_id, token_ids, amounts, memo);
+    }
+
+    /// Same as [`internal_mint`](MultiToken::internal_mint), recording ids minted for the
+    /// first time as `kind`. Panics if the registry is not enabled or if an id is already
+    /// registered as a different kind.
+    pub fn internal_mint_with_kind(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        kind: TokenKind,
+        memo: Option<String>,
+    ) {
+        require!(
+            self.id_kinds.is_some(),
+            "The token kind registry is not enabled"
+        );
+        self.internal_mint_kind(owner_id, token_ids, amounts, Some(kind), memo);
+    }
+
+    fn internal_mint_kind(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        kind: Option<TokenKind>,
+        memo: Option<String>,
+    ) {
+        unwrap_or_abort(check_amounts(token_ids, amounts));
+        for (token_id, &amount) in token_ids.iter().zip(amounts) {
+            self.internal_check_kind(token_id, amount, kind);
+            self.internal_deposit(owner_id, token_id, amount);
+            self.internal_touch(token_id);
+        }
+        #[cfg(feature = "validate")]
+        self.internal_validate(token_ids);
+        #[cfg(feature = "events")]
+        MtMint::emit_many_from(&[MtMint {
+            owner_id,
+            token_ids,
+            authorized_id: None,
+            memo: memo.as_deref(),
+        }]);
+        #[cfg(not(feature = "events"))]
+        let _ = memo;
+    }
+
+    /// Destroys `amounts` of `token_ids` held by `owner_id` and emits a single `MtBurn`
+    /// event. Approvals of a burned token are capped at what the owner has left, and go
+    /// away once nothing is left. Access control is up to the contract; the event names the
+    /// predecessor as `authorized_id` when it is not the owner. Panics if a balance is
+    /// insufficient.
+    pub fn internal_burn(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) {
+        self.internal_burn_by(
+            &env::predecessor_account_id(),
+            owner_id,
+            token_ids,
+            amounts,
+            memo,
+        );
+    }
+
+    /// Same as [`internal_burn`](MultiToken::internal_burn) with `burner_id` in place of the
+    /// predecessor: the event names it as `authorized_id` only if it is not the owner, so
+    /// that like for a transfer an owner burning their own tokens leaves it out.
+    pub(crate) fn internal_burn_by(
+        &mut self,
+        burner_id: &AccountId,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) {
+        unwrap_or_abort(check_amounts(token_ids, amounts));
+        for (token_id, &amount) in token_ids.iter().zip(amounts) {
+            self.internal_withdraw(owner_id, token_id, amount);
+            self.internal_cap_approvals(owner_id, token_id);
+            self.internal_forget_if_burned_out(token_id);
+        }
+        #[cfg(feature = "validate")]
+        self.internal_validate(token_ids);
+        #[cfg(feature = "events")]
+        MtBurn::emit_many_from(&[MtBurn {
+            owner_id,
+            token_ids,
+            authorized_id: Some(burner_id).filter(|id| *id != owner_id),
+            memo: memo.as_deref(),
+        }]);
+        #[cfg(not(feature = "events"))]
+        let _ = (burner_id, memo);
+    }
+
+    /// Transfers `amount` of `token_id` to `receiver_id`. `sender_id` is the owner, or with
+    /// `approval` an account the owner approved. Returns the owner the token came from.
+    pub fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) -> AccountId {
+        self.internal_batch_transfer(
+            sender_id,
+            receiver_id,
+            std::slice::from_ref(token_id),
+            &[amount],
+            approval.map(|approval| vec![Some(approval)]).as_deref(),
+            memo,
+        )
+    }
+
+    /// Like [`internal_transfer`](MultiToken::internal_transfer), but moves at most
+    /// `max_amount`, clamped to what the owner holds and, with `approval`, to what
+    /// `sender_id` is approved for. Returns what was actually moved. Panics if that is 0.
+    pub fn internal_transfer_up_to(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        max_amount: Balance,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) -> TransferReceipt {
+        let owner_id = approval
+            .as_ref()
+            .map_or(sender_id, |(owner_id, _)| owner_id);
+        let mut amount = max_amount.min(self.internal_balance_of(owner_id, token_id));
+        if let Some((owner_id, approval_id)) = approval.as_ref().filter(|(id, _)| id != sender_id) {
+            amount = amount.min(self.internal_approved_amount(
+                owner_id,
+                token_id,
+                sender_id,
+                Some(*approval_id),
+            ));
+        }
+        let old_owner_id =
+            self.internal_transfer(sender_id, receiver_id, token_id, amount, approval, memo);
+        TransferReceipt {
+            token_ids: vec![token_id.clone()],
+            amounts: vec![U128(amount)],
+            old_owner_id,
+            new_owner_id: receiver_id.clone(),
+        }
+    }
+
+    /// Non-standard counterpart of `mt_transfer` that transfers up to `max_amount`, see
+    /// [`internal_transfer_up_to`](MultiToken::internal_transfer_up_to), and returns a
+    /// [`TransferReceipt`] of what moved. Pass `u128::MAX` to transfer everything the
+    /// caller may. `mt_transfer` itself keeps returning nothing, as the standard says.
+    pub fn mt_transfer_up_to(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        max_amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) -> TransferReceipt {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer_up_to(
+            &sender_id,
+            &receiver_id,
+            &token_id,
+            max_amount.into(),
+            approval,
+            memo,
+        )
+    }
+
+    /// Pays each of `recipients` its amount of `token_id` from the predecessor, e.g. for
+    /// payouts, and emits a single `MtTransfer` event with one entry per recipient. The sum
+    /// is checked against the predecessor's balance before anything moves. Requires one
+    /// yoctoNEAR.
+    pub fn mt_distribute(
+        &mut self,
+        token_id: TokenId,
+        recipients: Vec<(AccountId, U128)>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        let recipients: Vec<(AccountId, Balance)> = recipients
+            .into_iter()
+            .map(|(receiver_id, amount)| (receiver_id, amount.0))
+            .collect();
+        self.internal_distribute(&sender_id, &token_id, &recipients, memo);
+    }
+
+    /// Like `mt_transfer` from the predecessor's own balance, but only if that balance of
+    /// `token_id` is exactly `expected_sender_balance` right before the transfer, and
+    /// otherwise panics with `"Balance precondition failed"`. Protocols that read the
+    /// balance in one step and act on it in another can so rule out that it changed in
+    /// between. Requires one yoctoNEAR.
+    pub fn mt_transfer_if_balance(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        expected_sender_balance: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        require!(
+            self.internal_balance_of(&sender_id, &token_id) == expected_sender_balance.0,
+            "Balance precondition failed"
+        );
+        self.internal_transfer(&sender_id, &receiver_id, &token_id, amount.0, None, memo);
+    }
+
+    /// Returns the summed total supply of `token_ids`, counting an id listed more than once
+    /// only once and an unknown id as 0. Each supply fits in a `u128`, but their sum may
+    /// not, and rather than saturate to a wrong total this panics with
+    /// `"Total supply overflow"` when it doesn't.
+    pub fn mt_total_supply(&self, token_ids: Vec<TokenId>) -> U128 {
+        unwrap_or_abort(if token_ids.is_empty() {
+            Err(MtError::NoTokenIds)
+        } else {
+            Ok(())
+        });
+        let token_ids: BTreeSet<TokenId> = token_ids.into_iter().collect();
+        let total = token_ids.iter().try_fold(0, |total: Balance, token_id| {
+            total.checked_add(self.total_supply.get(token_id).unwrap_or(0))
+        });
+        match total {
+            Some(total) => total.into(),
+            None => abort(MtError::TotalSupplyOverflow),
+        }
+    }
+
+    /// Transfers `amounts` of `token_ids` from the predecessor to `receiver_id` in batches of
+    /// `chunk_size` ids, each logged as its own `MtTransfer` event, for requests too large
+    /// for one batch. Before each chunk it checks that the gas left covers
+    /// [`mt_batch_transfer_gas_estimate`] for it, and otherwise stops cleanly, keeping the
+    /// chunks already moved. Returns how many ids were transferred, so the caller can send
+    /// the rest in another transaction. Requires one yoctoNEAR.
+    pub fn mt_transfer_chunked(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        chunk_size: u64,
+    ) -> u64 {
+        assert_one_yocto();
+        self.assert_not_paused();
+        require!(chunk_size > 0, "chunk_size must be a positive number");
+        unwrap_or_abort(if token_ids.len() == amounts.len() {
+            Ok(())
+        } else {
+            Err(MtError::LengthMismatch)
+        });
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(Balance::from).collect();
+        let chunk_size = usize::try_from(chunk_size).unwrap_or(usize::MAX);
+        let mut transferred = 0;
+        for (token_ids, amounts) in token_ids.chunks(chunk_size).zip(amounts.chunks(chunk_size)) {
+            let gas_left = env::prepaid_gas().0.saturating_sub(env::used_gas().0);
+            if gas_left < mt_batch_transfer_gas_estimate(token_ids, false) {
+                break;
+            }
+            self.internal_batch_transfer(&sender_id, &receiver_id, token_ids, amounts, None, None);
+            transferred += token_ids.len() as u64;
+        }
+        transferred
+    }
+
+    /// Moves each `(receiver_id, amount)` of `recipients` from `owner_id`, debiting the owner
+    /// once for the total, and emits a single `MtTransfer` event with an entry per
+    /// receiver. Recipients must be distinct.
+    pub fn internal_distribute(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        recipients: &[(AccountId, Balance)],
+        memo: Option<String>,
+    ) {
+        require!(!recipients.is_empty(), "No recipients provided");
+        let mut seen = BTreeSet::new();
+        let mut total: Balance = 0;
+        for (receiver_id, amount) in recipients {
+            require!(seen.insert(receiver_id), "Recipients must be distinct");
+            if *amount == 0 {
+                abort(MtError::ZeroAmount);
+            }
+            if receiver_id == owner_id {
+                abort(MtError::SelfTransfer);
+            }
+            if !self.internal_can_hold_tokens(receiver_id) {
+                abort(MtError::ReceiverNotRegistered);
+            }
+            if self
+                .internal_balance_of(receiver_id, token_id)
+                .checked_add(*amount)
+                .is_none()
+            {
+                abort(MtError::BalanceOverflow);
+            }
+            total = match total.checked_add(*amount) {
+                Some(total) => total,
+                None => abort(MtError::InsufficientBalance),
+            };
+        }
+        let balance = self.internal_balance_of(owner_id, token_id);
+        if balance < total {
+            abort(self.internal_insufficient_balance(owner_id, token_id, balance, total));
+        }
+        self.internal_withdraw(owner_id, token_id, total);
+        self.internal_cap_approvals(owner_id, token_id);
+        for (receiver_id, amount) in recipients {
+            self.internal_deposit(receiver_id, token_id, *amount);
+        }
+        self.internal_touch(token_id);
+        self.internal_record_volume(token_id, total);
+        #[cfg(feature = "validate")]
+        self.internal_validate(std::slice::from_ref(token_id));
+        #[cfg(feature = "events")]
+        {
+            let token_ids = [token_id.as_str()];
+            let amounts: Vec<[String; 1]> = recipients
+                .iter()
+                .map(|(_, amount)| [amount.to_string()])
+                .collect();
+            let amounts: Vec<[&str; 1]> = amounts.iter().map(|[a]| [a.as_str()]).collect();
+            let entries: Vec<MtTransfer> = recipients
+                .iter()
+                .zip(&amounts)
+                .map(|((receiver_id, _), amounts)| MtTransfer {
+                    old_owner_id: owner_id,
+                    new_owner_id: receiver_id,
+                    token_ids: &token_ids,
+                    amounts,
+                    authorized_id: None,
+                    approval_ids: None,
+                    memo: memo.as_deref(),
+                    memos: None,
+                })
+                .collect();
+            MtTransfer::emit_many(&entries);
+        }
+        #[cfg(not(feature = "events"))]
+        let _ = memo;
+    }
+
+    /// Moves every `amounts[i]` of `token_ids[i]` to `receiver_id` and emits a single
+    /// `MtTransfer` event for the whole batch. Returns the owner the tokens came from.
+    ///
+    /// Tokens are taken from `sender_id`, or, for entries with an approval, from the
+    /// approval's owner, spending that much of the amount the owner approved `sender_id`
+    /// for. All tokens of a batch must come from the same owner. Panics if any balance or
+    /// approved amount is insufficient, which reverts the entire batch. With storage
+    /// management, an unregistered `receiver_id` is rejected before anything is moved.
+    pub fn internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: Option<&[Option<(AccountId, u64)>]>,
+        memo: Option<String>,
+    ) -> AccountId {
+        unwrap_or_abort(self.try_internal_batch_transfer(
+            sender_id,
+            receiver_id,
+            token_ids,
+            amounts,
+            approvals,
+            memo,
+        ))
+    }
+
+    /// Like [`internal_batch_transfer`](MultiToken::internal_batch_transfer), but returns why
+    /// the batch can't be made instead of panicking. All checks run before the first write,
+    /// so nothing has changed when it returns an error. Only a receiver's storage deposit
+    /// too small for its new balances still panics, midway.
+    pub fn try_internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: Option<&[Option<(AccountId, u64)>]>,
+        memo: Option<String>,
+    ) -> Result<AccountId, MtError> {
+        let approvals = approvals.unwrap_or_default();
+        let coalesced;
+        let (token_ids, amounts, approvals) = match self.duplicate_token_ids {
+            DuplicateTokenIds::Allow => (token_ids, amounts, approvals),
+            _ if token_ids.iter().collect::<BTreeSet<_>>().len() == token_ids.len() => {
+                (token_ids, amounts, approvals)
+            }
+            DuplicateTokenIds::Reject => return Err(MtError::DuplicateTokenId),
+            DuplicateTokenIds::Coalesce => {
+                coalesced = coalesce(sender_id, token_ids, amounts, approvals)?;
+                (&coalesced.0[..], &coalesced.1[..], &coalesced.2[..])
+            }
+        };
+        let owner_id =
+            self.internal_move_batch(sender_id, receiver_id, token_ids, amounts, approvals)?;
+        #[cfg(feature = "events")]
+        {
+            let token_ids: Vec<&str> = token_ids.iter().map(TokenId::as_str).collect();
+            let amounts: Vec<String> = amounts.iter().map(Balance::to_string).collect();
+            let amounts: Vec<&str> = amounts.iter().map(String::as_str).collect();
+            let approval_ids: Option<Vec<u64>> = (&owner_id != sender_id).then(|| {
+                approvals
+                    .iter()
+                    .map(|approval| approval.as_ref().unwrap().1)
+                    .collect()
+            });
+            MtTransfer {
+                old_owner_id: &owner_id,
+                new_owner_id: receiver_id,
+                token_ids: &token_ids,
+                amounts: &amounts,
+                authorized_id: Some(sender_id).filter(|sender_id| *sender_id != &owner_id),
+                approval_ids: approval_ids.as_deref(),
+                memo: memo.as_deref(),
+                memos: None,
+            }
+            .emit();
+        }
+        #[cfg(not(feature = "events"))]
+        let _ = memo;
+        Ok(owner_id)
+    }
+
+    /// Like [`internal_batch_transfer`](MultiToken::internal_batch_transfer), with a memo per
+    /// token. The `MtTransfer` event gets one entry per distinct memo, in the order the memos
+    /// first appear, so ids sharing a memo are logged together and none loses its memo.
+    pub fn internal_batch_transfer_with_memos(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: Option<&[Option<(AccountId, u64)>]>,
+        memos: &[Option<String>],
+    ) -> AccountId {
+        require!(
+            memos.len() == token_ids.len(),
+            "token_ids and memos must have the same length"
+        );
+        let approvals = approvals.unwrap_or_default();
+        let owner_id = unwrap_or_abort(self.internal_move_batch(
+            sender_id,
+            receiver_id,
+            token_ids,
+            amounts,
+            approvals,
+        ));
+        #[cfg(feature = "events")]
+        {
+            // Entries are keyed by (old_owner, new_owner, authorized_id, memo), and all but
+            // the memo are the same for the whole batch.
+            let mut groups: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+            for (i, memo) in memos.iter().enumerate() {
+                let memo = memo.as_deref();
+                match groups.iter_mut().find(|(other, _)| *other == memo) {
+                    Some((_, indices)) => indices.push(i),
+                    None => groups.push((memo, vec![i])),
+                }
+            }
+            let amounts: Vec<String> = amounts.iter().map(Balance::to_string).collect();
+            let authorized = &owner_id != sender_id;
+            let pick = |indices: &[usize]| -> (Vec<&str>, Vec<&str>) {
+                indices
+                    .iter()
+                    .map(|&i| (token_ids[i].as_str(), amounts[i].as_str()))
+                    .unzip()
+            };
+            let columns: Vec<_> = groups.iter().map(|(_, indices)| pick(indices)).collect();
+            let approval_ids: Vec<Option<Vec<u64>>> = groups
+                .iter()
+                .map(|(_, indices)| {
+                    authorized.then(|| {
+                        indices
+                            .iter()
+                            .map(|&i| approvals[i].as_ref().unwrap().1)
+                            .collect()
+                    })
+                })
+                .collect();
+            let entries: Vec<MtTransfer> = groups
+                .iter()
+                .zip(columns.iter().zip(&approval_ids))
+                .map(
+                    |((memo, _), ((token_ids, amounts), approval_ids))| MtTransfer {
+                        old_owner_id: &owner_id,
+                        new_owner_id: receiver_id,
+                        token_ids,
+                        amounts,
+                        authorized_id: Some(sender_id).filter(|_| authorized),
+                        approval_ids: approval_ids.as_deref(),
+                        memo: *memo,
+                        memos: None,
+                    },
+                )
+                .collect();
+            MtTransfer::emit_many(&entries);
+        }
+        owner_id
+    }
+
+    /// Moves every `amounts[i]` of `token_ids[i]` from `owner_id` to `receiver_id`, each on
+    /// the authority of `authorizations[i]`: `(account_id, approval_id)` spends that much of
+    /// the owner's approval for `account_id`, and `None` moves the owner's tokens as the
+    /// owner. This is for custody contracts acting for several approved accounts in one
+    /// batch; access control, including that the contract may act for those accounts, is
+    /// up to the contract. The `MtTransfer` event gets one entry per distinct
+    /// `authorized_id`, in the order they first appear. Everything is checked before
+    /// anything moves.
+    pub fn internal_batch_transfer_on_behalf(
+        &mut self,
+        owner_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        authorizations: &[Option<(AccountId, u64)>],
+        memo: Option<String>,
+    ) {
+        unwrap_or_abort(if authorizations.len() == token_ids.len() {
+            Ok(())
+        } else {
+            Err(MtError::ApprovalsLengthMismatch)
+        });
+        unwrap_or_abort(
+            self.internal_check_batch_transfer(owner_id, receiver_id, token_ids, amounts, &[])
+                .map(|_| ()),
+        );
+        // Summed per approval, so an id listed more than once is checked for its whole amount.
+        let mut totals: BTreeMap<(&TokenId, &AccountId), Balance> = BTreeMap::new();
+        for ((token_id, &amount), authorization) in
+            token_ids.iter().zip(amounts).zip(authorizations)
+        {
+            if let Some((account_id, approval_id)) = authorization {
+                let total = totals.entry((token_id, account_id)).or_default();
+                *total += amount;
+                unwrap_or_abort(self.internal_check_approval(
+                    owner_id,
+                    token_id,
+                    account_id,
+                    *approval_id,
+                    *total,
+                ));
+            }
+        }
+        for ((token_id, &amount), authorization) in
+            token_ids.iter().zip(amounts).zip(authorizations)
+        {
+            if let Some((account_id, approval_id)) = authorization {
+                self.internal_spend_approval(owner_id, token_id, account_id, *approval_id, amount);
+            }
+        }
+        unwrap_or_abort(self.internal_move_batch(owner_id, receiver_id, token_ids, amounts, &[]));
+        #[cfg(feature = "events")]
+        {
+            let mut groups: Vec<(Option<&AccountId>, Vec<usize>)> = Vec::new();
+            for (i, authorization) in authorizations.iter().enumerate() {
+                let authorized_id = authorization.as_ref().map(|(account_id, _)| account_id);
+                match groups.iter_mut().find(|(other, _)| *other == authorized_id) {
+                    Some((_, indices)) => indices.push(i),
+                    None => groups.push((authorized_id, vec![i])),
+                }
+            }
+            let amounts: Vec<String> = amounts.iter().map(Balance::to_string).collect();
+            let columns: Vec<(Vec<&str>, Vec<&str>)> = groups
+                .iter()
+                .map(|(_, indices)| {
+                    indices
+                        .iter()
+                        .map(|&i| (token_ids[i].as_str(), amounts[i].as_str()))
+                        .unzip()
+                })
+                .collect();
+            let approval_ids: Vec<Option<Vec<u64>>> = groups
+                .iter()
+                .map(|(authorized_id, indices)| {
+                    authorized_id.map(|_| {
+                        indices
+                            .iter()
+                            .map(|&i| authorizations[i].as_ref().unwrap().1)
+                            .collect()
+                    })
+                })
+                .collect();
+            let entries: Vec<MtTransfer> = groups
+                .iter()
+                .zip(columns.iter().zip(&approval_ids))
+                .map(
+                    |((authorized_id, _), ((token_ids, amounts), approval_ids))| MtTransfer {
+                        old_owner_id: owner_id,
+                        new_owner_id: receiver_id,
+                        token_ids,
+                        amounts,
+                        authorized_id: *authorized_id,
+                        approval_ids: approval_ids.as_deref(),
+                        memo: memo.as_deref(),
+                        memos: None,
+                    },
+                )
+                .collect();
+            MtTransfer::emit_many(&entries);
+        }
+        #[cfg(not(feature = "events"))]
+        let _ = memo;
+    }
+
+    /// Checks and then applies a batch transfer, without emitting its event.
+    fn internal_move_batch(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: &[Option<(AccountId, u64)>],
+    ) -> Result<AccountId, MtError> {
+        let (owner_id, mut balances) = self.internal_check_batch_transfer(
+            sender_id,
+            receiver_id,
+            token_ids,
+            amounts,
+            approvals,
+        )?;
+        for (i, (token_id, &amount)) in token_ids.iter().zip(amounts).enumerate() {
+            if &owner_id != sender_id {
+                let (_, approval_id) = approvals[i].as_ref().unwrap();
+                self.internal_spend_approval(&owner_id, token_id, sender_id, *approval_id, amount);
+            }
+            // Kept current, so an id listed more than once starts from the moved balances.
+            let (owner_balance, receiver_balance) = balances.get_mut(token_id).unwrap();
+            self.internal_transfer_unchecked_balance(
+                &owner_id,
+                *owner_balance,
+                receiver_id,
+                *receiver_balance,
+                token_id,
+                amount,
+            );
+            *owner_balance -= amount;
+            *receiver_balance += amount;
+            self.internal_cap_approvals_at(&owner_id, token_id, *owner_balance);
+            self.internal_touch(token_id);
+            self.internal_record_volume(token_id, amount);
+        }
+        #[cfg(feature = "validate")]
+        self.internal_validate(token_ids);
+        Ok(owner_id)
+    }
+
+    /// Like [`internal_withdraw`](MultiToken::internal_withdraw) from `owner_id` followed by
+    /// [`internal_deposit`](MultiToken::internal_deposit) to `receiver_id`, but with both
+    /// balances of `token_id` already read. They are trusted to be current.
+    fn internal_transfer_unchecked_balance(
+        &mut self,
+        owner_id: &AccountId,
+        owner_balance: Balance,
+        receiver_id: &AccountId,
+        receiver_balance: Balance,
+        token_id: &TokenId,
+        amount: Balance,
+    ) {
+        self.internal_withdraw_from_balance(owner_id, token_id, owner_balance, amount);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_add_to_balance(receiver_id, token_id, receiver_balance, amount);
+        self.internal_track_storage(receiver_id, initial_storage_usage);
+    }
+
+    /// Checks everything [`try_internal_batch_transfer`](MultiToken::try_internal_batch_transfer)
+    /// needs before it moves anything. Returns the owner the tokens come from, and the
+    /// balances it read along the way, so moving the tokens needn't read them again.
+    fn internal_check_batch_transfer<'a>(
+        &self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &'a [TokenId],
+        amounts: &[Balance],
+        approvals: &[Option<(AccountId, u64)>],
+    ) -> Result<(AccountId, PreReadBalances<'a>), MtError> {
+        if token_ids.is_empty() {
+            return Err(MtError::NoTokenIds);
+        }
+        check_amounts(token_ids, amounts)?;
+        if !approvals.is_empty() && approvals.len() != token_ids.len() {
+            return Err(MtError::ApprovalsLengthMismatch);
+        }
+        let owner_id = batch_owner(sender_id, approvals)?;
+        if &owner_id == receiver_id {
+            return Err(MtError::SelfTransfer);
+        }
+        if !self.internal_can_hold_tokens(receiver_id) {
+            return Err(MtError::ReceiverNotRegistered);
+        }
+        // Summed per id, so an id listed more than once is checked for its whole amount.
+        let mut totals: BTreeMap<&TokenId, Balance> = BTreeMap::new();
+        for (i, (token_id, &amount)) in token_ids.iter().zip(amounts).enumerate() {
+            let total = totals.entry(token_id).or_default();
+            *total = total
+                .checked_add(amount)
+                .ok_or(MtError::InsufficientBalance)?;
+            if &owner_id != sender_id {
+                let (_, approval_id) = approvals[i].as_ref().unwrap();
+                self.internal_check_approval(&owner_id, token_id, sender_id, *approval_id, *total)?;
+            }
+        }
+        let mut balances = PreReadBalances::new();
+        for (token_id, total) in totals {
+            let owner_balance = self.internal_balance_of(&owner_id, token_id);
+            if owner_balance < total {
+                return Err(self.internal_insufficient_balance(
+                    &owner_id,
+                    token_id,
+                    owner_balance,
+                    total,
+                ));
+            }
+            let receiver_balance = self.internal_balance_of(receiver_id, token_id);
+            receiver_balance
+                .checked_add(total)
+                .ok_or(MtError::BalanceOverflow)?;
+            balances.insert(token_id, (owner_balance, receiver_balance));
+        }
+        Ok((owner_id, balances))
+    }
+
+    /// Transfers `amount` of `token_id` from the predecessor to `receiver_id`, then calls
+    /// `mt_on_transfer` on `receiver_id` followed by `mt_resolve_transfer` on this contract,
+    /// splitting the gas as `gas` says. Panics up front if the prepaid gas can't cover it.
+    ///
+    /// The returned promise resolves to what `mt_resolve_transfer` returns: a one-element
+    /// list holding the amount that stayed with the receiver, as `mt_transfer_call` does.
+    /// It is not a bare `U128` because a promise's result can only be reshaped by one
+    /// more callback, which every contract would have to expose next to
+    /// `mt_resolve_transfer`, and `#[ext_contract]` traits can't provide it by default.
+    /// This way the result passes straight through `mt_transfer_call`, whose return type
+    /// the standard fixes. Callers still have to require the one yoctoNEAR deposit
+    /// themselves.
+    #[allow(clippy::too_many_arguments)]
+    pub fn internal_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+        msg: String,
+        gas: TransferCallGas,
+    ) -> PromiseOrValue<Vec<U128>> {
+        self.internal_batch_transfer_call(
+            receiver_id,
+            vec![token_id],
+            vec![amount],
+            approval.map(|approval| vec![Some(approval)]),
+            memo,
+            msg,
+            gas,
+        )
+        .into()
+    }
+
+    /// Batched version of [`internal_transfer_call`](MultiToken::internal_transfer_call).
+    /// The receiver gets a single `mt_on_transfer` call covering all tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn internal_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+        gas: TransferCallGas,
+    ) -> Promise {
+        gas.assert_enough_prepaid_gas();
+        self.assert_receiver_allowed(&receiver_id);
+        let sender_id = env::predecessor_account_id();
+        let balances: Vec<Balance> = amounts.iter().map(|amount| amount.0).collect();
+        let owner_id = self.internal_batch_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &balances,
+            approvals.as_deref(),
+            memo,
+        );
+        // Approvals spent by the transfer, so the resolver can give back what is refunded.
+        let spent_approvals = (owner_id != sender_id).then(|| {
+            approvals
+                .unwrap_or_default()
+                .into_iter()
+                .map(|approval| {
+                    approval
+                        .map(|(_, approval_id)| BTreeMap::from([(sender_id.clone(), approval_id)]))
+                })
+                .collect()
+        });
+        // The receiver gets its reserved gas plus whatever is left, the resolver only its own.
+        ext_mt_receiver::ext(receiver_id.clone())
+            .with_static_gas(gas.mt_on_transfer)
+            .mt_on_transfer(
+                sender_id,
+                owner_id.clone(),
+                token_ids.clone(),
+                amounts.clone(),
+                msg,
+            )
+            .then(
+                ext_mt_resolver::ext(env::current_account_id())
+                    .with_static_gas(gas.resolve_transfer)
+                    .with_unused_gas_weight(0)
+                    .mt_resolve_transfer(
+                        owner_id,
+                        receiver_id,
+                        token_ids,
+                        amounts,
+                        spent_approvals,
+                    ),
+            )
+    }
+
+    /// Registers `token_id` on its first mint and enforces a supply of at most 1 for
+    /// non-fungible ids. Does nothing when the registry is disabled.
+    fn internal_check_kind(
+        &mut self,
+        token_id: &TokenId,
+        amount: Balance,
+        kind: Option<TokenKind>,
+    ) {
+        let id_kinds = match self.id_kinds.as_mut() {
+            Some(id_kinds) => id_kinds,
+            None => return,
+        };
+        let registered = match (id_kinds.get(token_id), kind) {
+            (Some(registered), Some(kind)) if registered != kind => {
+                require!(
+                    false,
+                    format!(
+                        "Token {} is already registered as {:?}",
+                        token_id, registered
+                    )
+                );
+                registered
+            }
+            (Some(registered), _) => registered,
+            (None, kind) => {
+                let kind = kind.unwrap_or(TokenKind::Fungible);
+                id_kinds.insert(token_id, &kind);
+                kind
+            }
+        };
+        if registered == TokenKind::NonFungible {
+            require!(
+                amount == 1,
+                "A non-fungible token can only be minted with an amount of 1"
+            );
+            require!(
+                self.total_supply.get(token_id).unwrap_or(0) == 0,
+                format!("Non-fungible token {} has already been minted", token_id)
+            );
+        }
+    }
+
+    /// With `remove_on_zero_supply`, forgets `token_id` once burns left none of it.
+    /// Transfers pass through a zero supply too, but only ever briefly.
+    fn internal_forget_if_burned_out(&mut self, token_id: &TokenId) {
+        if self.remove_on_zero_supply && self.total_supply.get(token_id) == Some(0) {
+            self.total_supply.remove(token_id);
+            if let Some(all_token_ids) = self.all_token_ids.as_mut() {
+                all_token_ids.remove(token_id);
+            }
+        }
+    }
+
+    /// Records the current block timestamp as the last transfer of `token_id`, if
+    /// transfer timestamps are tracked.
+    pub(crate) fn internal_touch(&mut self, token_id: &TokenId) {
+        if let Some(last_transfer_at) = self.last_transfer_at.as_mut() {
+            last_transfer_at.insert(token_id, &env::block_timestamp());
+        }
+    }
+
+    /// Adds `amount` to the transfer volume of `token_id`, if transfer volume is tracked.
+    pub(crate) fn internal_record_volume(&mut self, token_id: &TokenId, amount: Balance) {
+        if let Some(transfer_volume) = self.transfer_volume.as_mut() {
+            let volume = transfer_volume
+                .get(token_id)
+                .unwrap_or(0)
+                .checked_add(amount);
+            if volume.is_none() {
+                require!(false, "Transfer volume overflow");
+            }
+            transfer_volume.insert(token_id, &volume.unwrap());
+        }
+    }
+
+    /// Returns the total amount of `token_id` transferred since transfer volume has been
+    /// tracked, see [`with_transfer_volume`](MultiToken::with_transfer_volume). `"0"` if it
+    /// isn't.
+    pub fn mt_transfer_volume(&self, token_id: TokenId) -> U128 {
+        self.transfer_volume
+            .as_ref()
+            .and_then(|transfer_volume| transfer_volume.get(&token_id))
+            .unwrap_or(0)
+            .into()
+    }
+
+    fn internal_update_owners(
+        &mut self,
+        token_id: &TokenId,
+        f: impl FnOnce(&mut UnorderedSet<AccountId>),
+    ) {
+        let mut owners = self.owners_by_id.get(token_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                [
+                    self.prefix.as_slice(),
+                    b"h",
+                    &env::sha256(token_id.as_str().as_bytes()),
+                ]
+                .concat(),
+            )
+        });
+        f(&mut owners);
+        self.owners_by_id.insert(token_id, &owners);
+    }
+
+    fn internal_update_tokens_per_owner(
+        &mut self,
+        account_id: &AccountId,
+        f: impl FnOnce(&mut UnorderedSet<TokenId>),
+    ) {
+        if let Some(tokens_per_owner) = self.tokens_per_owner.as_mut() {
+            let mut tokens = tokens_per_owner.get(account_id).unwrap_or_else(|| {
+                UnorderedSet::new(
+                    [
+                        self.prefix.as_slice(),
+                        b"u",
+                        &env::sha256(account_id.as_bytes()),
+                    ]
+                    .concat(),
+                )
+            });
+            f(&mut tokens);
+            tokens_per_owner.insert(account_id, &tokens);
+        }
+    }
+
+    pub(crate) fn internal_token(&self, token_id: TokenId) -> Option<Token> {
+        count_storage_read();
+        let supply = self.total_supply.get(&token_id)?;
+        let owner_id = self
+            .owners_by_id
+            .get(&token_id)
+            .filter(|owners| owners.len() == 1)
+            .and_then(|owners| owners.iter().next());
+        let metadata = self
+            .token_metadata_by_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&token_id));
+        let approved_account_ids =
+            self.approvals_by_id
+                .as_ref()
+                .zip(owner_id.as_ref())
+                .map(|(by_id, owner_id)| {
+                    by_id
+                        .get(&(owner_id.clone(), token_id.clone()))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(account_id, (approval_id, _))| (account_id, approval_id))
+                        .collect()
+                });
+        Some(Token {
+            decimals: metadata.as_ref().and_then(|metadata| metadata.decimals),
+            symbol: metadata
+                .as_ref()
+                .and_then(|metadata| metadata.symbol.clone()),
+            metadata: metadata.map(Into::into),
+            approved_account_ids,
+            last_transfer_at: self
+                .last_transfer_at
+                .as_ref()
+                .and_then(|by_id| by_id.get(&token_id)),
+            ..Token::new(token_id, owner_id, supply.into())
+        })
+    }
+}
+
+/// Storage key marking that an instance was created under `prefix`.
+fn initialized_key(prefix: &[u8]) -> Vec<u8> {
+    [prefix, b"i"].concat()
+}
+
+/// TokenId -> (owner balance, receiver balance), as read by a batch transfer's checks.
+type PreReadBalances<'a> = BTreeMap<&'a TokenId, (Balance, Balance)>;
+
+/// Checks that `amounts` has one positive amount per entry of `token_ids`.
+pub(crate) fn check_amounts(token_ids: &[TokenId], amounts: &[Balance]) -> Result<(), MtError> {
+    if token_ids.len() != amounts.len() {
+        return Err(MtError::LengthMismatch);
+    }
+    if amounts.contains(&0) {
+        return Err(MtError::ZeroAmount);
+    }
+    Ok(())
+}
+
+/// Variants of the plain operations that also return the data of the event they logged,
+/// for wrapping contracts that forward or check it without parsing logs.
+#[cfg(feature = "events")]
+impl MultiToken {
+    /// Same as [`internal_mint`](MultiToken::internal_mint), returning the logged `MtMint`.
+    pub fn internal_mint_returning_event(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) -> MtMintOwned {
+        self.internal_mint(owner_id, token_ids, amounts, memo.clone());
+        MtMintOwned {
+            owner_id: owner_id.clone(),
+            token_ids: token_ids.iter().map(TokenId::to_string).collect(),
+            authorized_id: None,
+            memo,
+        }
+    }
+
+    /// Same as [`internal_transfer`](MultiToken::internal_transfer), returning the logged
+    /// `MtTransfer`.
+    pub fn internal_transfer_returning_event(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) -> MtTransferOwned {
+        let approval_id = approval.as_ref().map(|(_, approval_id)| *approval_id);
+        let owner_id = self.internal_transfer(
+            sender_id,
+            receiver_id,
+            token_id,
+            amount,
+            approval,
+            memo.clone(),
+        );
+        let authorized = &owner_id != sender_id;
+        MtTransferOwned {
+            old_owner_id: owner_id,
+            new_owner_id: receiver_id.clone(),
+            token_ids: vec![token_id.to_string()],
+            amounts: vec![amount.to_string()],
+            authorized_id: Some(sender_id.clone()).filter(|_| authorized),
+            approval_ids: approval_id.filter(|_| authorized).map(|id| vec![id]),
+            memo,
+            memos: None,
+        }
+    }
+
+    /// Same as [`internal_burn`](MultiToken::internal_burn), returning the logged `MtBurn`.
+    pub fn internal_burn_returning_event(
+        &mut self,
+        owner_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) -> MtBurnOwned {
+        self.internal_burn(owner_id, token_ids, amounts, memo.clone());
+        MtBurnOwned {
+            owner_id: owner_id.clone(),
+            token_ids: token_ids.iter().map(TokenId::to_string).collect(),
+            authorized_id: Some(env::predecessor_account_id()).filter(|id| id != owner_id),
+            memo,
+        }
+    }
+}
+
+/// Token ids, with their amounts and approvals, of a batch whose duplicates were merged.
+type CoalescedBatch = (Vec<TokenId>, Vec<Balance>, Vec<Option<(AccountId, u64)>>);
+
+/// Merges the entries of each id listed more than once into the first of them, summing
+/// the amounts. Fails the way the batch would have if its entries disagree on an approval.
+fn coalesce(
+    sender_id: &AccountId,
+    token_ids: &[TokenId],
+    amounts: &[Balance],
+    approvals: &[Option<(AccountId, u64)>],
+) -> Result<CoalescedBatch, MtError> {
+    check_amounts(token_ids, amounts)?;
+    if !approvals.is_empty() && approvals.len() != token_ids.len() {
+        return Err(MtError::ApprovalsLengthMismatch);
+    }
+    batch_owner(sender_id, approvals)?;
+    let mut batch: CoalescedBatch = (Vec::new(), Vec::new(), Vec::new());
+    for (i, (token_id, &amount)) in token_ids.iter().zip(amounts).enumerate() {
+        let approval = approvals.get(i).cloned().flatten();
+        match batch.0.iter().position(|other| other == token_id) {
+            Some(j) => {
+                // Only one approval ID per id can be current.
+                if !approvals.is_empty() && batch.2[j] != approval {
+                    return Err(MtError::InvalidApprovalId);
+                }
+                batch.1[j] = batch.1[j]
+                    .checked_add(amount)
+                    .ok_or(MtError::InsufficientBalance)?;
+            }
+            None => {
+                batch.0.push(token_id.clone());
+                batch.1.push(amount);
+                if !approvals.is_empty() {
+                    batch.2.push(approval);
+                }
+            }
+        }
+    }
+    Ok(batch)
+}
+
+/// The account a batch is taken from: the owner named by its approvals, or `sender_id`.
+pub(crate) fn batch_owner(
+    sender_id: &AccountId,
+    approvals: &[Option<(AccountId, u64)>],
+) -> Result<AccountId, MtError> {
+    let mut owners = approvals.iter().map(|approval| {
+        approval
+            .as_ref()
+            .map_or(sender_id, |(owner_id, _)| owner_id)
+    });
+    let owner_id = owners.next().unwrap_or(sender_id);
+    if !owners.all(|other| other == owner_id) {
+        return Err(MtError::MixedOwners);
+    }
+    Ok(owner_id.clone())
+}
+
+impl MultiTokenCore for MultiToken {
+    fn mt_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_id,
+            amount.into(),
+            approval,
+            memo,
+        );
+    }
+
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(Balance::from).collect();
+        self.internal_batch_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &amounts,
+            approvals.as_deref(),
+            memo,
+        );
+    }
+
+    fn mt_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        assert_one_yocto();
+        self.assert_not_paused();
+        self.internal_transfer_call(
+            receiver_id,
+            token_id,
+            amount,
+            approval,
+            memo,
+            msg,
+            TransferCallGas::default(),
+        )
+    }
+
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        assert_one_yocto();
+        self.assert_not_paused();
+        self.internal_batch_transfer_call(
+            receiver_id,
+            token_ids,
+            amounts,
+            approvals,
+            memo,
+            msg,
+            TransferCallGas::default(),
+        )
+        .into()
+    }
+
+    fn mt_token(&self, token_ids: Vec<TokenId>) -> Vec<Option<Token>> {
+        token_ids
+            .into_iter()
+            .map(|token_id| self.internal_token(token_id))
+            .collect()
+    }
+
+    fn mt_balance_of(&self, account_id: AccountId, token_id: TokenId) -> U128 {
+        self.internal_balance_of(&account_id, &token_id).into()
+    }
+
+    fn mt_batch_balance_of(&self, account_id: AccountId, token_ids: Vec<TokenId>) -> Vec<U128> {
+        require!(!token_ids.is_empty(), "No token_ids provided");
+        token_ids
+            .iter()
+            .map(|token_id| self.internal_balance_of(&account_id, token_id).into())
+            .collect()
+    }
+
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128> {
+        self.total_supply.get(&token_id).map(U128)
+    }
+
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>> {
+        token_ids
+            .iter()
+            .map(|token_id| self.total_supply.get(token_id).map(U128))
+            .collect()
+    }
+}
+
+impl MultiToken {
+    /// Returns the amount of each token kept by `receiver_id`, after refunding to
+    /// `previous_owner_id` whatever the `mt_on_transfer` result asks to give back.
+    /// A failed or unparsable result refunds everything, and so does `true` from a
+    /// single-token receiver (see [`SingleTokenReceiver`](super::SingleTokenReceiver)),
+    /// while `false` keeps everything. A refund never exceeds the
+    /// amount sent or the receiver's current balance: whatever the receiver spent or
+    /// burned in the meantime counts as used. Refunded amounts are credited back to the
+    /// `approvals` the transfer spent.
+    ///
+    /// This never panics, as that would leave the tokens with the receiver. With storage
+    /// management, the refund is charged to `previous_owner_id` even past its storage
+    /// deposit, or burned from the receiver, with an `MtBurn` event, if it is no longer
+    /// registered.
+    pub fn internal_resolve_transfer(
+        &mut self,
+        previous_owner_id: &AccountId,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<BTreeMap<AccountId, u64>>>>,
+    ) -> Vec<U128> {
+        let amounts: Vec<Balance> = amounts.into_iter().map(Balance::from).collect();
+
+        // Get the unused amounts from the `mt_on_transfer` call result.
+        let unused_amounts = match env::promise_result(0) {
+            PromiseResult::NotReady => env::abort(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<Vec<U128>>(&value) {
+                    Ok(unused) if unused.len() == amounts.len() => amounts
+                        .iter()
+                        .zip(unused)
+                        .map(|(&amount, unused)| std::cmp::min(amount, unused.0))
+                        .collect(),
+                    _ => match near_sdk::serde_json::from_slice::<bool>(&value) {
+                        Ok(false) => vec![0; amounts.len()],
+                        _ => amounts.clone(),
+                    },
+                }
+            }
+            PromiseResult::Failed => amounts.clone(),
+        };
+
+        let mut refunded_ids = Vec::new();
+        let mut refunded_amounts = Vec::new();
+        let mut burned_ids = Vec::new();
+        let mut approvals = approvals.unwrap_or_default().into_iter();
+        let used = token_ids
+            .iter()
+            .zip(amounts.iter().zip(unused_amounts))
+            .map(|(token_id, (&amount, unused_amount))| {
+                let spent_approvals = approvals.next().flatten();
+                let receiver_balance = self.internal_balance_of(&receiver_id, token_id);
+                let refund_amount = std::cmp::min(receiver_balance, unused_amount);
+                if refund_amount > 0 {
+                    self.internal_withdraw(&receiver_id, token_id, refund_amount);
+                    if self.internal_can_hold_tokens(previous_owner_id) {
+                        let initial_storage_usage = env::storage_usage();
+                        self.internal_add_balance(previous_owner_id, token_id, refund_amount);
+                        for (account_id, approval_id) in spent_approvals.into_iter().flatten() {
+                            self.internal_restore_approval(
+                                previous_owner_id,
+                                token_id,
+                                &account_id,
+                                approval_id,
+                                refund_amount,
+                            );
+                        }
+                        self.internal_charge_storage(previous_owner_id, initial_storage_usage);
+                        self.internal_touch(token_id);
+                        refunded_ids.push(token_id.as_str());
+                        refunded_amounts.push(refund_amount.to_string());
+                    } else {
+                        self.internal_forget_if_burned_out(token_id);
+                        burned_ids.push(token_id.as_str());
+                    }
+                }
+                U128(amount - refund_amount)
+            })
+            .collect();
+
+        #[cfg(feature = "events")]
+        if !refunded_ids.is_empty() {
+            let refunded_amounts: Vec<&str> = refunded_amounts.iter().map(String::as_str).collect();
+            MtTransfer {
+                old_owner_id: &receiver_id,
+                new_owner_id: previous_owner_id,
+                token_ids: &refunded_ids,
+                amounts: &refunded_amounts,
+                authorized_id: None,
+                approval_ids: None,
+                memo: None,
+                memos: None,
+            }
+            .emit();
+        }
+        #[cfg(feature = "events")]
+        if !burned_ids.is_empty() {
+            MtBurn::builder(&receiver_id, &burned_ids)
+                .memo("refund_unregistered")
+                .build()
+                .emit();
+        }
+        used
+    }
+}
+
+impl MultiTokenResolver for MultiToken {
+    fn mt_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<BTreeMap<AccountId, u64>>>>,
+    ) -> Vec<U128> {
+        assert_self();
+        self.internal_resolve_transfer(
+            &previous_owner_id,
+            receiver_id,
+            token_ids,
+            amounts,
+            approvals,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::approval::MultiTokenApproval;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, RuntimeFeesConfig, VMConfig};
+
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m");
+        mt.internal_mint(
+            &accounts(0),
+            &["gold".into(), "sword".into()],
+            &[100, 1],
+            None,
+        );
+        mt
+    }
+
+    #[test]
+    fn mint_deposits_supply() {
+        let mt = setup();
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        assert_eq!(mt.mt_supply("silver".into()), None);
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"alice","token_ids":["gold","sword"]}]}"#
+        );
+    }
+
+    #[test]
+    fn transfer_moves_balance() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(30), None, None);
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(0), vec!["gold".into(), "silver".into()]),
+            [U128(70), U128(0)]
+        );
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(30));
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[1],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold"],"amounts":["30"]}]}"#
+        );
+    }
+
+    #[test]
+    fn plain_transfer_to_a_contract_makes_no_call() {
+        let mut mt = setup();
+        let dex: AccountId = "dex.near".parse().unwrap();
+        mt.mt_transfer(dex.clone(), "gold".into(), U128(30), None, None);
+        assert_eq!(mt.mt_balance_of(dex, "gold".into()), U128(30));
+        assert!(near_sdk::test_utils::get_created_receipts().is_empty());
+    }
+
+    #[test]
+    fn distribute_pays_every_recipient() {
+        let mut mt = setup();
+        mt.mt_distribute(
+            "gold".into(),
+            vec![
+                (accounts(1), U128(10)),
+                (accounts(2), U128(20)),
+                (accounts(3), U128(30)),
+            ],
+            Some("payout".into()),
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(40));
+        assert_eq!(
+            [1, 2, 3].map(|i| mt.mt_balance_of(accounts(i), "gold".into())),
+            [U128(10), U128(20), U128(30)]
+        );
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[1],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold"],"amounts":["10"],"memo":"payout"},{"old_owner_id":"alice","new_owner_id":"charlie","token_ids":["gold"],"amounts":["20"],"memo":"payout"},{"old_owner_id":"alice","new_owner_id":"danny","token_ids":["gold"],"amounts":["30"],"memo":"payout"}]}"#
+        );
+    }
+
+    #[test]
+    fn distribute_checks_the_sum_up_front() {
+        let mut mt = setup();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mt.mt_distribute(
+                "gold".into(),
+                vec![(accounts(1), U128(60)), (accounts(2), U128(60))],
+                None,
+            )
+        }));
+        assert!(result.is_err());
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(0));
+    }
+
+    #[test]
+    fn chunked_transfer_reports_partial_progress() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .prepaid_gas(near_sdk::Gas(150_000_000_000_000))
+            .build());
+        let mut mt = MultiToken::new(b"m");
+        let token_ids: Vec<TokenId> = (0..100).map(|i| format!("t{}", i).into()).collect();
+        mt.internal_mint(&accounts(0), &token_ids, &[1; 100], None);
+        let transferred =
+            mt.mt_transfer_chunked(accounts(1), token_ids.clone(), vec![U128(1); 100], 10);
+        assert!(transferred > 0 && transferred < 100);
+        assert_eq!(transferred % 10, 0);
+        #[cfg(feature = "events")]
+        {
+            let transfers = near_sdk::test_utils::get_logs()
+                .iter()
+                .filter(|log| log.contains(r#""event":"mt_transfer""#))
+                .count();
+            assert_eq!(transfers as u64, transferred / 10);
+        }
+        // The balance checks below cost gas too, so they run with a fresh budget.
+        testing_env!(VMContextBuilder::new().build());
+        for (i, token_id) in token_ids.into_iter().enumerate() {
+            let moved = (i as u64) < transferred;
+            assert_eq!(
+                mt.mt_balance_of(accounts(1), token_id.clone()),
+                U128(moved as u128)
+            );
+            assert_eq!(
+                mt.mt_balance_of(accounts(0), token_id),
+                U128(!moved as u128)
+            );
+        }
+    }
+
+    #[test]
+    fn batch_balance_of_pads_with_zeros() {
+        let mt = setup();
+        assert_eq!(
+            mt.mt_batch_balance_of(
+                accounts(0),
+                vec![
+                    "silver".into(),
+                    "sword".into(),
+                    "gold".into(),
+                    "silver".into(),
+                ]
+            ),
+            [U128(0), U128(1), U128(100), U128(0)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No token_ids provided")]
+    fn batch_balance_of_requires_token_ids() {
+        let _ = setup().mt_batch_balance_of(accounts(0), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No token_ids provided")]
+    fn batch_transfer_requires_token_ids() {
+        setup().mt_batch_transfer(accounts(1), vec![], vec![], None, None);
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn batch_with_shared_memo_logs_one_entry() {
+        let mut mt = setup();
+        mt.internal_batch_transfer_with_memos(
+            &accounts(0),
+            &accounts(1),
+            &["gold".into(), "sword".into()],
+            &[10, 1],
+            None,
+            &[Some("gift".into()), Some("gift".into())],
+        );
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[1],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold","sword"],"amounts":["10","1"],"memo":"gift"}]}"#
+        );
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn batch_with_differing_memos_logs_an_entry_per_memo() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(0), &["gem".into()], &[3], None);
+        mt.internal_batch_transfer_with_memos(
+            &accounts(0),
+            &accounts(1),
+            &["gold".into(), "sword".into(), "gem".into()],
+            &[10, 1, 3],
+            None,
+            &[Some("gift".into()), None, Some("gift".into())],
+        );
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold","gem"],"amounts":["10","3"],"memo":"gift"},{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["sword"],"amounts":["1"]}]}"#
+        );
+        assert_eq!(mt.mt_balance_of(accounts(1), "gem".into()), U128(3));
+    }
+
+    /// Alice approved bob for 30 gold and charlie for the sword, under approval IDs 0 and 1.
+    fn approved_to_two_accounts() -> MultiToken {
+        let mut mt = setup().with_approvals();
+        mt.internal_mint(&accounts(0), &["gem".into()], &[3], None);
+        mt.mt_approve(vec!["gold".into()], vec![U128(30)], accounts(1), None);
+        mt.mt_approve(vec!["sword".into()], vec![U128(1)], accounts(2), None);
+        mt
+    }
+
+    #[test]
+    fn batch_on_behalf_logs_an_entry_per_authorized_id() {
+        let mut mt = approved_to_two_accounts();
+        mt.internal_batch_transfer_on_behalf(
+            &accounts(0),
+            &accounts(3),
+            &["gold".into(), "sword".into(), "gem".into(), "gold".into()],
+            &[10, 1, 3, 20],
+            &[
+                Some((accounts(1), 0)),
+                Some((accounts(2), 1)),
+                None,
+                Some((accounts(1), 0)),
+            ],
+            None,
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(
+                accounts(3),
+                vec!["gold".into(), "sword".into(), "gem".into()]
+            ),
+            [U128(30), U128(1), U128(3)]
+        );
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), None),
+            0
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"danny","token_ids":["gold","gold"],"amounts":["10","20"],"authorized_id":"bob","approval_ids":[0,0]},{"old_owner_id":"alice","new_owner_id":"danny","token_ids":["sword"],"amounts":["1"],"authorized_id":"charlie","approval_ids":[1]},{"old_owner_id":"alice","new_owner_id":"danny","token_ids":["gem"],"amounts":["3"]}]}"#
+        );
+    }
+
+    #[test]
+    fn batch_on_behalf_checks_every_approval_first() {
+        let mut mt = approved_to_two_accounts();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mt.internal_batch_transfer_on_behalf(
+                &accounts(0),
+                &accounts(3),
+                &["gold".into(), "sword".into()],
+                &[10, 1],
+                &[Some((accounts(1), 0)), Some((accounts(1), 0))],
+                None,
+            )
+        }));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(message, "Sender is not approved");
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), None),
+            30
+        );
+    }
+
+    #[test]
+    fn batch_transfer_reads_each_balance_once() {
+        let mut mt = setup();
+        take_storage_reads();
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "sword".into(), "gold".into()],
+            vec![U128(10), U128(1), U128(20)],
+            None,
+            None,
+        );
+        // The owner's and the receiver's balance of gold and of the sword.
+        assert_eq!(take_storage_reads(), 4);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(70));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(30));
+        assert_eq!(mt.mt_balance_of(accounts(1), "sword".into()), U128(1));
+    }
+
+    #[test]
+    fn try_batch_transfer_reports_each_error() {
+        let mut mt = setup();
+        let (alice, bob, carol) = (accounts(0), accounts(1), accounts(2));
+        let gold: TokenId = "gold".into();
+        assert_eq!(
+            mt.try_internal_batch_transfer(&alice, &bob, &[], &[], None, None),
+            Err(MtError::NoTokenIds)
+        );
+        let mut attempt =
+            |receiver_id: &AccountId,
+             amounts: &[Balance],
+             approvals: Option<&[Option<(AccountId, u64)>]>| {
+                mt.try_internal_batch_transfer(
+                    &alice,
+                    receiver_id,
+                    std::slice::from_ref(&gold),
+                    amounts,
+                    approvals,
+                    None,
+                )
+                .unwrap_err()
+                .to_string()
+            };
+        assert_eq!(
+            attempt(&bob, &[1, 2], None),
+            "token_ids and amounts must have the same length"
+        );
+        assert_eq!(
+            attempt(&bob, &[0], None),
+            "The amount should be a positive number"
+        );
+        assert_eq!(
+            attempt(&bob, &[1], Some(&[None, None])),
+            "token_ids and approvals must have the same length"
+        );
+        assert_eq!(
+            attempt(&alice, &[1], None),
+            "Sender and receiver should be different"
+        );
+        assert_eq!(
+            attempt(&bob, &[101], None),
+            "The account doesn't have enough balance"
+        );
+        assert_eq!(
+            attempt(&bob, &[1], Some(&[Some((carol.clone(), 1))])),
+            "Approval Management is not supported"
+        );
+        let two_owners = [Some((bob.clone(), 1)), Some((carol, 1))];
+        assert_eq!(
+            mt.try_internal_batch_transfer(
+                &alice,
+                &bob,
+                &[gold.clone(), gold.clone()],
+                &[1, 1],
+                Some(&two_owners),
+                None,
+            )
+            .unwrap_err()
+            .to_string(),
+            "All tokens of a batch must have the same owner"
+        );
+        assert_eq!(mt.mt_balance_of(alice.clone(), gold.clone()), U128(100));
+        assert_eq!(
+            mt.try_internal_batch_transfer(&alice, &bob, &[gold], &[1], None, None),
+            Ok(alice)
+        );
+    }
+
+    #[test]
+    fn try_batch_transfer_sums_repeated_ids() {
+        let mut mt = setup();
+        let gold: TokenId = "gold".into();
+        assert_eq!(
+            mt.try_internal_batch_transfer(
+                &accounts(0),
+                &accounts(1),
+                &[gold.clone(), gold.clone()],
+                &[60, 60],
+                None,
+                None,
+            ),
+            Err(MtError::InsufficientBalance)
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), gold), U128(100));
+    }
+
+    #[test]
+    fn coalesced_batch_logs_one_entry_per_id() {
+        let mut mt = setup().with_duplicate_token_ids(DuplicateTokenIds::Coalesce);
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "sword".into(), "gold".into()],
+            vec![U128(30), U128(1), U128(20)],
+            None,
+            None,
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(1), vec!["gold".into(), "sword".into()]),
+            [U128(50), U128(1)]
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[1..],
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold","sword"],"amounts":["50","1"]}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesced_batch_still_checks_the_summed_amount() {
+        let mut mt = setup().with_duplicate_token_ids(DuplicateTokenIds::Coalesce);
+        let gold: TokenId = "gold".into();
+        assert_eq!(
+            mt.try_internal_batch_transfer(
+                &accounts(0),
+                &accounts(1),
+                &[gold.clone(), gold.clone()],
+                &[60, 60],
+                None,
+                None,
+            ),
+            Err(MtError::InsufficientBalance)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate token_id in batch")]
+    fn duplicate_ids_can_be_rejected() {
+        let mut mt = setup().with_duplicate_token_ids(DuplicateTokenIds::Reject);
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "gold".into()],
+            vec![U128(1), U128(1)],
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn batch_transfer_emits_one_event() {
+        let mut mt = setup();
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(100), U128(1)],
+            None,
+            Some("trade".into()),
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(1), vec!["gold".into(), "sword".into()]),
+            [U128(100), U128(1)]
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs()[1..],
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["gold","sword"],"amounts":["100","1"],"memo":"trade"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn transfer_up_to_reports_the_clamped_amount() {
+        let mut mt = setup();
+        let receipt = mt.mt_transfer_up_to(accounts(1), "gold".into(), U128(150), None, None);
+        assert_eq!(
+            receipt,
+            TransferReceipt {
+                token_ids: vec!["gold".into()],
+                amounts: vec![U128(100)],
+                old_owner_id: accounts(0),
+                new_owner_id: accounts(1),
+            }
+        );
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn transfer_more_than_balance() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(101), None, None);
+    }
+
+    #[test]
+    fn supply_is_the_sum_of_balances() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(1), &["gold".into()], &[30], None);
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(130)));
+        mt.internal_withdraw(&accounts(0), &"gold".into(), 100);
+        mt.internal_withdraw(&accounts(1), &"gold".into(), 10);
+        assert_eq!(
+            mt.mt_batch_supply(vec!["silver".into(), "gold".into(), "sword".into()]),
+            [None, Some(U128(20)), Some(U128(1))]
+        );
+        assert_eq!(
+            mt.mt_balance_of(accounts(0), "gold".into()).0
+                + mt.mt_balance_of(accounts(1), "gold".into()).0,
+            20
+        );
+    }
+
+    #[test]
+    fn burn_destroys_supply() {
+        let mut mt = setup();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(3))
+            .build());
+        mt.internal_burn(
+            &accounts(0),
+            &["gold".into(), "sword".into()],
+            &[40, 1],
+            Some("melt".into()),
+        );
+        assert_eq!(
+            mt.mt_batch_supply(vec!["gold".into(), "sword".into()]),
+            [Some(U128(60)), Some(U128(0))]
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "sword".into()), U128(0));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold","sword"],"authorized_id":"danny","memo":"melt"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn burn_more_than_balance() {
+        let mut mt = setup();
+        mt.internal_burn(&accounts(0), &["sword".into()], &[2], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Balance overflow")]
+    fn deposit_past_max_balance() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(1), &["diamond".into()], &[u128::MAX], None);
+        mt.internal_deposit(&accounts(1), &"diamond".into(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Total supply overflow")]
+    fn deposit_past_max_supply() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(1), &["diamond".into()], &[u128::MAX], None);
+        mt.internal_deposit(&accounts(2), &"diamond".into(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn withdraw_more_than_held() {
+        let mut mt = setup();
+        mt.internal_withdraw(&accounts(0), &"sword".into(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Total supply underflow")]
+    fn withdraw_past_total_supply() {
+        let mut mt = setup();
+        mt.total_supply.insert(&"sword".into(), &0);
+        mt.internal_withdraw(&accounts(0), &"sword".into(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender and receiver should be different")]
+    fn transfer_to_self() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(0), "gold".into(), U128(1), None, None);
+    }
+
+    #[test]
+    fn token_owner_is_sole_holder() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+        let tokens = mt.mt_token(vec!["gold".into(), "sword".into(), "silver".into()]);
+        assert_eq!(tokens[0].as_ref().unwrap().owner_id, None);
+        assert_eq!(tokens[1].as_ref().unwrap().owner_id, Some(accounts(0)));
+        assert!(tokens[2].is_none());
+    }
+
+    #[test]
+    fn token_carries_display_hints() {
+        let mut mt = setup().with_token_metadata();
+        mt.internal_set_token_metadata(
+            &"gold".into(),
+            &TokenMetadata {
+                title: Some("Gold".into()),
+                description: None,
+                media: None,
+                media_hash: None,
+                copies: None,
+                issued_at: None,
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+                decimals: Some(2),
+                symbol: Some("GOLD".into()),
+            },
+        );
+        let tokens = mt.mt_token(vec!["gold".into(), "sword".into()]);
+        let gold = tokens[0].as_ref().unwrap();
+        assert_eq!(gold.supply, U128(100));
+        assert_eq!(
+            gold.metadata.as_ref().unwrap().title.as_deref(),
+            Some("Gold")
+        );
+        assert_eq!(gold.decimals, Some(2));
+        assert_eq!(gold.symbol.as_deref(), Some("GOLD"));
+        let sword = tokens[1].as_ref().unwrap();
+        assert!(sword.metadata.is_none());
+        assert_eq!(sword.decimals, None);
+    }
+
+    #[test]
+    fn transfer_updates_last_transfer_at() {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .block_timestamp(1_000)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_transfer_timestamps();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        let last_transfer_at = |mt: &MultiToken| {
+            mt.mt_token(vec!["gold".into()])[0]
+                .as_ref()
+                .unwrap()
+                .last_transfer_at
+        };
+        assert_eq!(last_transfer_at(&mt), Some(1_000));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .block_timestamp(2_000)
+            .build());
+        mt.mt_transfer(accounts(1), "gold".into(), U128(30), None, None);
+        assert_eq!(last_transfer_at(&mt), Some(2_000));
+        // Without the flag nothing is tracked.
+        let mut untracked = MultiToken::new(b"n");
+        untracked.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        assert_eq!(last_transfer_at(&untracked), None);
+    }
+
+    fn titled(title: &str) -> TokenMetadata {
+        TokenMetadata {
+            title: Some(title.into()),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+            decimals: None,
+            symbol: None,
+        }
+    }
+
+    /// Mints 5 gem titled "Gem" under `policy`, then 5 more titled "Fake".
+    fn remint(policy: MetadataOnRemint) -> MultiToken {
+        let mut mt = setup()
+            .with_token_metadata()
+            .with_metadata_on_remint(policy);
+        let gem: TokenId = "gem".into();
+        let gems = std::slice::from_ref(&gem);
+        mt.internal_mint_with_metadata(&accounts(0), gems, &[5], &[titled("Gem")], None);
+        mt.internal_mint_with_metadata(&accounts(1), gems, &[5], &[titled("Fake")], None);
+        mt
+    }
+
+    fn title(mt: &MultiToken) -> Option<String> {
+        mt.mt_token(vec!["gem".into()])[0]
+            .clone()
+            .and_then(|token| token.metadata)
+            .and_then(|metadata| metadata.title)
+    }
+
+    #[test]
+    #[should_panic(expected = "Token gem already has metadata")]
+    fn remint_with_metadata_is_rejected_by_default() {
+        remint(MetadataOnRemint::default());
+    }
+
+    #[test]
+    fn remint_can_ignore_new_metadata() {
+        let mt = remint(MetadataOnRemint::Ignore);
+        assert_eq!(mt.mt_supply("gem".into()), Some(U128(10)));
+        assert_eq!(title(&mt).as_deref(), Some("Gem"));
+    }
+
+    #[test]
+    fn remint_can_overwrite_metadata() {
+        let mt = remint(MetadataOnRemint::Overwrite);
+        assert_eq!(mt.mt_supply("gem".into()), Some(U128(10)));
+        assert_eq!(title(&mt).as_deref(), Some("Fake"));
+    }
+
+    fn metadata(mt: &MultiToken) -> TokenMetadata {
+        mt.token_metadata_by_id
+            .as_ref()
+            .and_then(|token_metadata_by_id| token_metadata_by_id.get(&"gem".into()))
+            .unwrap()
+    }
+
+    #[test]
+    fn metadata_timestamps_are_populated() {
+        let mut mt = setup().with_token_metadata();
+        testing_env!(VMContextBuilder::new().block_timestamp(1_000).build());
+        let gems = ["gem".into()];
+        mt.internal_mint_with_metadata(&accounts(0), &gems, &[5], &[titled("Gem")], None);
+        assert_eq!(metadata(&mt).issued_at.as_deref(), Some("1000"));
+        assert_eq!(metadata(&mt).updated_at, None);
+
+        testing_env!(VMContextBuilder::new().block_timestamp(2_000).build());
+        mt.internal_update_token_metadata(&"gem".into(), titled("Shiny gem"));
+        assert_eq!(metadata(&mt).title.as_deref(), Some("Shiny gem"));
+        assert_eq!(metadata(&mt).issued_at.as_deref(), Some("1000"));
+        assert_eq!(metadata(&mt).updated_at.as_deref(), Some("2000"));
+
+        testing_env!(VMContextBuilder::new().block_timestamp(3_000).build());
+        mt.internal_update_token_metadata(&"gem".into(), titled("Gem"));
+        assert_eq!(metadata(&mt).updated_at.as_deref(), Some("3000"));
+    }
+
+    #[test]
+    fn given_metadata_timestamps_are_kept() {
+        let mut mt = setup().with_token_metadata();
+        let gem = TokenMetadata {
+            issued_at: Some("42".into()),
+            ..titled("Gem")
+        };
+        mt.internal_mint_with_metadata(&accounts(0), &["gem".into()], &[5], &[gem], None);
+        assert_eq!(metadata(&mt).issued_at.as_deref(), Some("42"));
+        mt.internal_update_token_metadata(
+            &"gem".into(),
+            TokenMetadata {
+                updated_at: Some("43".into()),
+                ..titled("Gem")
+            },
+        );
+        assert_eq!(metadata(&mt).updated_at.as_deref(), Some("43"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token gem has no metadata")]
+    fn update_requires_existing_metadata() {
+        let mut mt = setup().with_token_metadata();
+        mt.internal_update_token_metadata(&"gem".into(), titled("Gem"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Already initialized")]
+    fn new_twice_panics() {
+        let _mt = setup();
+        MultiToken::new(b"m");
+    }
+
+    #[test]
+    fn migrate_keeps_the_stored_collections() {
+        let mut mt = setup();
+        mt.next_approval_id = 3;
+        let mut migrated = MultiToken::migrate(b"m");
+        migrated.next_approval_id = mt.next_approval_id;
+        assert_eq!(
+            migrated.mt_balance_of(accounts(0), "gold".into()),
+            U128(100)
+        );
+        assert_eq!(migrated.next_approval_id, 3);
+    }
+
+    #[test]
+    fn prefixed_instances_are_independent() {
+        let mt = setup();
+        let mut other = MultiToken::new(b"n");
+        other.internal_mint(&accounts(1), &["gold".into()], &[5], None);
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        assert_eq!(other.mt_supply("gold".into()), Some(U128(5)));
+        assert_eq!(other.mt_balance_of(accounts(0), "gold".into()), U128(0));
+    }
+
+    #[test]
+    fn enough_gas_for_transfer_call() {
+        testing_env!(VMContextBuilder::new()
+            .prepaid_gas(Gas(300_000_000_000_000))
+            .build());
+        assert_enough_gas_for_transfer_call();
+    }
+
+    #[test]
+    #[should_panic(expected = "More gas is required")]
+    fn not_enough_gas_for_transfer_call() {
+        testing_env!(VMContextBuilder::new()
+            .prepaid_gas(Gas(30_000_000_000_000))
+            .build());
+        assert_enough_gas_for_transfer_call();
+    }
+
+    #[test]
+    fn batch_transfer_gas_estimate_scales_with_batch_size() {
+        let ids = |n: usize| {
+            (0..n)
+                .map(|i| i.to_string().into())
+                .collect::<Vec<TokenId>>()
+        };
+        let one = mt_batch_transfer_gas_estimate(&ids(1), false);
+        let ten = mt_batch_transfer_gas_estimate(&ids(10), false);
+        assert!(ten > one);
+        assert_eq!(ten - one, 9 * GAS_ESTIMATE_PER_TOKEN.0);
+        assert!(mt_batch_transfer_gas_estimate(&ids(10), true) > ten);
+    }
+
+    #[test]
+    fn id_kinds_are_recorded_on_first_mint() {
+        let mut mt = setup().with_id_kinds();
+        mt.internal_mint(&accounts(0), &["silver".into()], &[10], None);
+        mt.internal_mint_with_kind(
+            &accounts(0),
+            &["crown".into()],
+            &[1],
+            TokenKind::NonFungible,
+            None,
+        );
+        let id_kinds = mt.id_kinds.as_ref().unwrap();
+        assert_eq!(id_kinds.get(&"silver".into()), Some(TokenKind::Fungible));
+        assert_eq!(id_kinds.get(&"crown".into()), Some(TokenKind::NonFungible));
+        // Fungible ids can be minted again.
+        mt.internal_mint(&accounts(1), &["silver".into()], &[5], None);
+        assert_eq!(mt.mt_supply("silver".into()), Some(U128(15)));
+    }
+
+    #[test]
+    #[should_panic(expected = "A non-fungible token can only be minted with an amount of 1")]
+    fn non_fungible_mint_above_one() {
+        let mut mt = setup().with_id_kinds();
+        mt.internal_mint_with_kind(
+            &accounts(0),
+            &["crown".into()],
+            &[2],
+            TokenKind::NonFungible,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Non-fungible token crown has already been minted")]
+    fn non_fungible_second_mint() {
+        let mut mt = setup().with_id_kinds();
+        mt.internal_mint_with_kind(
+            &accounts(0),
+            &["crown".into()],
+            &[1],
+            TokenKind::NonFungible,
+            None,
+        );
+        mt.internal_mint(&accounts(1), &["crown".into()], &[1], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token silver is already registered as Fungible")]
+    fn kind_mismatch() {
+        let mut mt = setup().with_id_kinds();
+        mt.internal_mint(&accounts(0), &["silver".into()], &[1], None);
+        mt.internal_mint_with_kind(
+            &accounts(0),
+            &["silver".into()],
+            &[1],
+            TokenKind::NonFungible,
+            None,
+        );
+    }
+
+    #[test]
+    fn batch_transfer_call_calls_receiver_with_batch() {
+        let mut mt = setup();
+        let _ = mt.mt_batch_transfer_call(
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+            None,
+            "stake".into(),
+        );
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(50));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(1));
+        match &receipts[0].actions[0] {
+            VmAction::FunctionCall {
+                function_name,
+                args,
+                ..
+            } => {
+                assert_eq!(function_name, "mt_on_transfer");
+                assert_eq!(
+                    std::str::from_utf8(args).unwrap(),
+                    r#"{"sender_id":"alice","previous_owner_id":"alice","token_ids":["gold","sword"],"amounts":["50","1"],"msg":"stake"}"#
+                );
+            }
+            action => panic!("unexpected action {:?}", action),
+        }
+        assert!(matches!(
+            &receipts[1].actions[0],
+            VmAction::FunctionCall { function_name, .. } if function_name == "mt_resolve_transfer"
+        ));
+    }
+
+    #[test]
+    fn transfer_call_uses_given_gas_split() {
+        let mut mt = setup();
+        let gas = TransferCallGas {
+            mt_on_transfer: Gas(40_000_000_000_000),
+            resolve_transfer: Gas(10_000_000_000_000),
+        };
+        let _ = mt.internal_transfer_call(
+            accounts(1),
+            "gold".into(),
+            U128(5),
+            None,
+            None,
+            "".into(),
+            gas,
+        );
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let calls: Vec<(&AccountId, &str, Gas)> = receipts
+            .iter()
+            .map(|receipt| match &receipt.actions[0] {
+                VmAction::FunctionCall {
+                    function_name, gas, ..
+                } => (&receipt.receiver_id, function_name.as_str(), *gas),
+                action => panic!("unexpected action {:?}", action),
+            })
+            .collect();
+        assert_eq!(calls[0].0, &accounts(1));
+        assert_eq!(calls[0].1, "mt_on_transfer");
+        assert!(calls[0].2 >= gas.mt_on_transfer);
+        assert_eq!(
+            calls[1],
+            (
+                &env::current_account_id(),
+                "mt_resolve_transfer",
+                gas.resolve_transfer
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "More gas is required")]
+    fn transfer_call_rejects_gas_split_above_prepaid() {
+        let mut mt = setup();
+        let _ = mt.internal_transfer_call(
+            accounts(1),
+            "gold".into(),
+            U128(5),
+            None,
+            None,
+            "".into(),
+            TransferCallGas {
+                mt_on_transfer: Gas(300_000_000_000_000),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn resolve_with(result: PromiseResult) {
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(accounts(5))
+                .predecessor_account_id(accounts(5))
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
+    fn sent_to_receiver() -> MultiToken {
+        let mut mt = setup();
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+            None,
+        );
+        mt
+    }
+
+    #[test]
+    fn resolve_partial_refund() {
+        let mut mt = sent_to_receiver();
+        // The receiver keeps 20 gold and the sword, and gives 30 gold back.
+        let unused = near_sdk::serde_json::to_vec(&[U128(30), U128(0)]).unwrap();
+        resolve_with(PromiseResult::Successful(unused));
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+        );
+        assert_eq!(used, [U128(20), U128(1)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(80));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(20));
+        assert_eq!(mt.mt_balance_of(accounts(1), "sword".into()), U128(1));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["gold"],"amounts":["30"]}]}"#
+            ]
+        );
+    }
+
+    /// Runs a whole `mt_transfer_call` of 50 gold to bob, who gives `unused` back, and
+    /// counts the `MtTransfer` events logged by the transfer and by the resolver.
+    #[cfg(feature = "events")]
+    fn transfer_call_chain_events(unused: u128) -> (usize, usize) {
+        let count_transfers = || {
+            near_sdk::test_utils::get_logs()
+                .iter()
+                .filter(|log| log.contains(r#""event":"mt_transfer""#))
+                .count()
+        };
+        let mut mt = setup();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .prepaid_gas(near_sdk::Gas(150_000_000_000_000))
+            .build());
+        let _ = mt.mt_transfer_call(accounts(1), "gold".into(), U128(50), None, None, "".into());
+        let forward = count_transfers();
+        resolve_with(PromiseResult::Successful(
+            near_sdk::serde_json::to_vec(&[U128(unused)]).unwrap(),
+        ));
+        mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(50)],
+            None,
+        );
+        (forward, count_transfers())
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn transfer_call_logs_the_transfer_once() {
+        assert_eq!(transfer_call_chain_events(0), (1, 0));
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn resolver_logs_only_the_refund() {
+        assert_eq!(transfer_call_chain_events(30), (1, 1));
+    }
+
+    #[test]
+    fn resolve_caps_refund_at_amount_sent() {
+        let mut mt = sent_to_receiver();
+        // Asking for more gold back than was sent only returns what was sent.
+        let unused = near_sdk::serde_json::to_vec(&[U128(500), U128(0)]).unwrap();
+        resolve_with(PromiseResult::Successful(unused));
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+        );
+        assert_eq!(used, [U128(0), U128(1)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+    }
+
+    #[test]
+    fn resolve_clamps_refund_to_receiver_balance() {
+        let mut mt = sent_to_receiver();
+        // The receiver burns 40 of the 50 gold, then asks for all 50 back.
+        mt.internal_withdraw(&accounts(1), &"gold".into(), 40);
+        let unused = near_sdk::serde_json::to_vec(&[U128(50), U128(0)]).unwrap();
+        resolve_with(PromiseResult::Successful(unused));
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+        );
+        assert_eq!(used, [U128(40), U128(1)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(60));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(0));
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(60)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["gold"],"amounts":["10"]}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_failed_promise_refunds_everything() {
+        let mut mt = sent_to_receiver();
+        resolve_with(PromiseResult::Failed);
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(50), U128(1)],
+            None,
+        );
+        assert_eq!(used, [U128(0), U128(0)]);
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(0), vec!["gold".into(), "sword".into()]),
+            [U128(100), U128(1)]
+        );
+    }
+
+    #[test]
+    fn resolve_single_token_receiver_answer() {
+        let mut mt = sent_to_receiver();
+        // A single-token receiver answers `false` to keep what it was sent.
+        resolve_with(PromiseResult::Successful(b"false".to_vec()));
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(50)],
+            None,
+        );
+        assert_eq!(used, [U128(50)]);
+
+        resolve_with(PromiseResult::Successful(b"true".to_vec()));
+        let used = mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(50)],
+            None,
+        );
+        assert_eq!(used, [U128(0)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is private")]
+    fn resolve_requires_self() {
+        let mut mt = sent_to_receiver();
+        mt.mt_resolve_transfer(accounts(0), accounts(1), vec![], vec![], None);
+    }
+
+    #[test]
+    fn assert_self_allows_the_contract_itself() {
+        resolve_with(PromiseResult::Failed);
+        assert_self();
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn returned_events_match_the_logs() {
+        use crate::multi_token::events::{parse_event, Nep246EventKindOwned};
+        let last_event = || parse_event(near_sdk::test_utils::get_logs().last().unwrap()).unwrap();
+        let mut mt = setup().with_approvals();
+        let mint = mt.internal_mint_returning_event(
+            &accounts(0),
+            &["gem".into()],
+            &[3],
+            Some("new".into()),
+        );
+        assert_eq!(last_event(), Nep246EventKindOwned::MtMint(vec![mint]));
+
+        mt.mt_approve(vec!["gold".into()], vec![U128(30)], accounts(1), None);
+        let transfer = mt.internal_transfer_returning_event(
+            &accounts(1),
+            &accounts(2),
+            &"gold".into(),
+            10,
+            Some((accounts(0), 0)),
+            None,
+        );
+        assert_eq!(transfer.authorized_id, Some(accounts(1)));
+        assert_eq!(
+            last_event(),
+            Nep246EventKindOwned::MtTransfer(vec![transfer])
+        );
+
+        let burn = mt.internal_burn_returning_event(&accounts(2), &["gold".into()], &[10], None);
+        assert_eq!(burn.authorized_id, Some(accounts(0)));
+        assert_eq!(last_event(), Nep246EventKindOwned::MtBurn(vec![burn]));
+    }
+
+    #[test]
+    fn transfer_volume_accumulates() {
+        let mut mt = setup().with_transfer_volume();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(30), None, None);
+        mt.mt_batch_transfer(
+            accounts(2),
+            vec!["gold".into(), "sword".into(), "gold".into()],
+            vec![U128(10), U128(1), U128(5)],
+            None,
+            None,
+        );
+        mt.internal_mint(&accounts(0), &["gold".into()], &[50], None);
+        mt.internal_burn(&accounts(0), &["gold".into()], &[50], None);
+        assert_eq!(mt.mt_transfer_volume("gold".into()), U128(45));
+        assert_eq!(mt.mt_transfer_volume("sword".into()), U128(1));
+        assert_eq!(mt.mt_transfer_volume("silver".into()), U128(0));
+    }
+
+    #[test]
+    fn transfer_volume_ignores_refunds() {
+        let mut mt = setup().with_transfer_volume();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(50), None, None);
+        resolve_with(PromiseResult::Failed);
+        mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(50)],
+            None,
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(mt.mt_transfer_volume("gold".into()), U128(50));
+    }
+
+    #[test]
+    fn transfer_if_balance_moves_on_a_match() {
+        let mut mt = setup();
+        mt.mt_transfer_if_balance(accounts(1), "gold".into(), U128(30), U128(100), None);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(70));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(30));
+    }
+
+    #[test]
+    #[should_panic(expected = "Balance precondition failed")]
+    fn transfer_if_balance_rejects_a_mismatch() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+        mt.mt_transfer_if_balance(accounts(1), "gold".into(), U128(30), U128(100), None);
+    }
+
+    /// Supplies close to `u128::MAX`, where adding them up naively would wrap around.
+    mod total_supply {
+        use super::*;
+
+        const HALF: Balance = u128::MAX / 2 + 1;
+
+        /// Alice holds `HALF` gold and `HALF - 1` silver, bob another `HALF` of diamond.
+        fn setup() -> MultiToken {
+            testing_env!(VMContextBuilder::new().build());
+            let mut mt = MultiToken::new(b"m");
+            mt.internal_mint(
+                &accounts(0),
+                &["gold".into(), "silver".into()],
+                &[HALF, HALF - 1],
+                None,
+            );
+            mt.internal_mint(&accounts(1), &["diamond".into()], &[HALF], None);
+            mt
+        }
+
+        #[test]
+        fn sums_up_to_u128_max() {
+            let mt = setup();
+            assert_eq!(
+                mt.mt_total_supply(vec!["gold".into(), "silver".into()]),
+                U128(u128::MAX)
+            );
+        }
+
+        #[test]
+        fn counts_each_id_once() {
+            let mt = setup();
+            assert_eq!(
+                mt.mt_total_supply(vec!["gold".into(), "gold".into(), "ruby".into()]),
+                U128(HALF)
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "Total supply overflow")]
+        fn panics_when_the_sum_overflows() {
+            let _ = setup().mt_total_supply(vec!["gold".into(), "diamond".into()]);
+        }
+
+        #[test]
+        #[should_panic(expected = "Total supply overflow")]
+        fn supply_of_one_id_cannot_overflow() {
+            let mut mt = setup();
+            mt.internal_mint(&accounts(1), &["gold".into()], &[HALF], None);
+        }
+
+        #[test]
+        fn each_supply_stays_exact() {
+            let mt = setup();
+            assert_eq!(
+                mt.mt_batch_supply(vec!["gold".into(), "diamond".into()]),
+                [Some(U128(HALF)), Some(U128(HALF))]
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::error::MtError;
+use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+/// Outcome of [`MultiToken::mt_transfer_dry_run`]. When the transfer would fail, the
+/// balances are the current ones, as nothing would change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct MtTransferDryRun {
+    pub would_succeed: bool,
+    /// The message the transfer would panic with.
+    pub error: Option<String>,
+    /// Balance of the account the tokens are taken from: the sender, or the owner named
+    /// by the approval.
+    pub sender_balance_after: U128,
+    pub receiver_balance_after: U128,
+}
+
+impl MultiToken {
+    /// Previews `mt_transfer` of `amount` of `token_id` from `sender_id` to `receiver_id`,
+    /// going through the same checks without changing state or emitting events. Contracts
+    /// can expose it as a view method for wallets. The attached deposit is not checked.
+    pub fn mt_transfer_dry_run(
+        &self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+    ) -> MtTransferDryRun {
+        let amount = amount.0;
+        let owner_id = approval
+            .as_ref()
+            .map_or(&sender_id, |(owner_id, _)| owner_id);
+        let owner_balance = self.internal_balance_of(owner_id, &token_id);
+        let receiver_balance = self.internal_balance_of(&receiver_id, &token_id);
+
+        let result = if amount == 0 {
+            Err(MtError::ZeroAmount)
+        } else if owner_id == &receiver_id {
+            Err(MtError::SelfTransfer)
+        } else {
+            approval
+                .as_ref()
+                .filter(|_| owner_id != &sender_id)
+                .map_or(Ok(0), |(_, approval_id)| {
+                    self.internal_check_approval(
+                        owner_id,
+                        &token_id,
+                        &sender_id,
+                        *approval_id,
+                        amount,
+                    )
+                })
+                .and_then(|_| {
+                    owner_balance.checked_sub(amount).ok_or_else(|| {
+                        self.internal_insufficient_balance(
+                            owner_id,
+                            &token_id,
+                            owner_balance,
+                            amount,
+                        )
+                    })
+                })
+                .and_then(|owner_balance| {
+                    receiver_balance
+                        .checked_add(amount)
+                        .map(|receiver_balance| (owner_balance, receiver_balance))
+                        .ok_or(MtError::BalanceOverflow)
+                })
+        };
+
+        match result {
+            Ok((owner_balance, receiver_balance)) => MtTransferDryRun {
+                would_succeed: true,
+                error: None,
+                sender_balance_after: owner_balance.into(),
+                receiver_balance_after: receiver_balance.into(),
+            },
+            Err(err) => MtTransferDryRun {
+                would_succeed: false,
+                error: Some(err.to_string()),
+                sender_balance_after: owner_balance.into(),
+                receiver_balance_after: receiver_balance.into(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::approval::MultiTokenApproval;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds 10 gold and has approved bob for 4 of it under approval ID 0.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_approvals();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[10], None);
+        mt.mt_approve(vec!["gold".into()], vec![U128(4)], accounts(1), None);
+        mt
+    }
+
+    fn dry_run(
+        mt: &MultiToken,
+        sender_id: AccountId,
+        amount: u128,
+        approval: Option<(AccountId, u64)>,
+    ) -> MtTransferDryRun {
+        mt.mt_transfer_dry_run(
+            sender_id,
+            accounts(2),
+            "gold".into(),
+            U128(amount),
+            approval,
+        )
+    }
+
+    fn failure(error: &str, sender_balance: u128, receiver_balance: u128) -> MtTransferDryRun {
+        MtTransferDryRun {
+            would_succeed: false,
+            error: Some(error.to_string()),
+            sender_balance_after: U128(sender_balance),
+            receiver_balance_after: U128(receiver_balance),
+        }
+    }
+
+    #[test]
+    fn dry_run_reports_balances_after() {
+        let mt = setup();
+        let expected = MtTransferDryRun {
+            would_succeed: true,
+            error: None,
+            sender_balance_after: U128(7),
+            receiver_balance_after: U128(3),
+        };
+        assert_eq!(dry_run(&mt, accounts(0), 3, None), expected);
+        assert_eq!(
+            dry_run(&mt, accounts(1), 3, Some((accounts(0), 0))),
+            expected
+        );
+        assert_eq!(mt.internal_balance_of(&accounts(0), &"gold".into()), 10);
+        assert_eq!(mt.internal_balance_of(&accounts(2), &"gold".into()), 0);
+        assert_eq!(
+            mt.internal_approved_amount(&accounts(0), &"gold".into(), &accounts(1), None),
+            4
+        );
+        // Only the setup's mint and approval were logged.
+        #[cfg(feature = "events")]
+        assert_eq!(near_sdk::test_utils::get_logs().len(), 2);
+    }
+
+    #[test]
+    fn dry_run_reports_failures() {
+        let mt = setup();
+        assert_eq!(
+            dry_run(&mt, accounts(0), 0, None),
+            failure("The amount should be a positive number", 10, 0)
+        );
+        assert_eq!(
+            dry_run(&mt, accounts(0), 11, None),
+            failure("The account doesn't have enough balance", 10, 0)
+        );
+        assert_eq!(
+            mt.mt_transfer_dry_run(accounts(0), accounts(0), "gold".into(), U128(1), None),
+            failure("Sender and receiver should be different", 10, 10)
+        );
+        assert_eq!(
+            dry_run(&mt, accounts(3), 1, Some((accounts(0), 0))),
+            failure("Sender is not approved", 10, 0)
+        );
+        assert_eq!(
+            dry_run(&mt, accounts(1), 1, Some((accounts(0), 7))),
+            failure("Invalid approval ID", 10, 0)
+        );
+        assert_eq!(
+            dry_run(&mt, accounts(1), 5, Some((accounts(0), 0))),
+            failure("Transfer amount exceeds the approved amount", 10, 0)
+        );
+    }
+
+    #[test]
+    fn dry_run_without_approval_extension() {
+        let mut mt = MultiToken::new(b"n");
+        mt.internal_mint(&accounts(0), &["gold".into()], &[10], None);
+        assert_eq!(
+            dry_run(&mt, accounts(1), 1, Some((accounts(0), 0))),
+            failure("Approval Management is not supported", 10, 0)
+        );
+    }
+}
@@ -1,60 +1,97 @@
 mod core_impl;
 
+mod dry_run;
 mod receiver;
 mod resolver;
 
 pub use self::core_impl::*;
 
-pub use self::receiver::MultiTokenReceiver;
+pub use self::dry_run::MtTransferDryRun;
+
+pub use self::receiver::{assert_batch_within, CallerAllowlist, MultiTokenReceiver};
+#[allow(deprecated)]
+pub use self::receiver::{mt_on_transfer_single, SingleTokenReceiver};
 pub use self::resolver::MultiTokenResolver;
 
 use crate::multi_token::token::{Token, TokenId};
+use near_sdk::json_types::U128;
 use near_sdk::AccountId;
-use near_sdk::PromiseOrValue;
+use near_sdk::{ext_contract, PromiseOrValue};
 
 /// Used for all multi tokens. The specification for the
-/// [core non-fungible token standard] lays out the reasoning for each method.
-/// It's important to check out [MultiTokenReceiver](crate::non_fungible_token::core::MultiTokenReceiver)
-/// and [MultiTokenResolver](crate::non_fungible_token::core::MultiTokenResolver) to
+/// [core multi token standard] lays out the reasoning for each method.
+/// It's important to check out [MultiTokenReceiver](crate::multi_token::core::MultiTokenReceiver)
+/// and [MultiTokenResolver](crate::multi_token::core::MultiTokenResolver) to
 /// understand how the cross-contract call work.
 ///
+/// [core multi token standard]: <https://nomicon.io/Standards/Tokens/MultiToken/Core>
+#[ext_contract(ext_mt_core)]
 pub trait MultiTokenCore {
-    /// Simple transfer. Transfer a given `token_id` from current owner to
-    /// `receiver_id`.
+    /// Simple transfer. Transfer `amount` of a given `token_id` from the current
+    /// owner to `receiver_id`.
     ///
     /// Requirements
     /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security purposes
     /// * Contract MUST panic if called by someone other than token owner or,
     ///   if using Approval Management, one of the approved accounts
-    /// * `approval_id` is for use with Approval Management,
-    ///   see <https://nomicon.io/Standards/MultiToken/ApprovalManagement.html>
+    /// * Contract MUST panic if the owner's balance of `token_id` is lower than `amount`
+    /// * `approval` is for use with Approval Management,
+    ///   see <https://nomicon.io/Standards/Tokens/MultiToken/ApprovalManagement>
     /// * If using Approval Management, contract MUST nullify approved accounts on
-    ///   successful transfer.
-    /// * TODO: needed? Both accounts must be registered with the contract for transfer to
-    ///   succeed. See see <https://nomicon.io/Standards/StorageManagement.html>
+    ///   successful transfer. Approvals are kept but capped at what the owner has
+    ///   left, whether the owner or an approved account transferred.
     ///
     /// Arguments:
     /// * `receiver_id`: the valid NEAR account receiving the token
     /// * `token_id`: the token to transfer
-    /// * `approval_id`: expected approval ID. A number smaller than
-    ///    2^53, and therefore representable as JSON. See Approval Management
-    ///    standard for full explanation.
+    /// * `amount`: the number of tokens to transfer
+    /// * `approval`: the owner and expected approval ID when transferring on
+    ///   behalf of an approved account. The ID is a number smaller than 2^53,
+    ///   and therefore representable as JSON. See Approval Management
+    ///   standard for full explanation. An approval naming the caller as owner
+    ///   is ignored, its ID is not checked.
     /// * `memo` (optional): for use cases that may benefit from indexing or
-    ///    providing information for a transfer
+    ///   providing information for a transfer
     fn mt_transfer(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        approval_id: Option<u64>,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    );
+
+    /// Batched version of [`mt_transfer`](MultiTokenCore::mt_transfer).
+    ///
+    /// Requirements
+    /// * All requirements of `mt_transfer` apply to every token in the batch
+    /// * Contract MUST panic if `token_ids` is empty
+    /// * Contract MUST panic if `token_ids` and `amounts` differ in length, or
+    ///   if `approvals` is given and differs in length from `token_ids`
+    ///
+    /// Arguments:
+    /// * `receiver_id`: the valid NEAR account receiving the tokens
+    /// * `token_ids`: the tokens to transfer
+    /// * `amounts`: the number of tokens to transfer, one entry per token ID
+    /// * `approvals` (optional): one optional `(owner_id, approval_id)` pair
+    ///   per token ID
+    /// * `memo` (optional): for use cases that may benefit from indexing or
+    ///   providing information for a transfer
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
         memo: Option<String>,
     );
 
-    /// Transfer token and call a method on a receiver contract. A successful
+    /// Transfer tokens and call a method on a receiver contract. A successful
     /// workflow will end in a success execution outcome to the callback on the MT
     /// contract at the method `mt_resolve_transfer`.
     ///
     /// You can think of this as being similar to attaching native NEAR tokens to a
-    /// function call. It allows you to attach any Non-Fungible Token in a call to a
+    /// function call. It allows you to attach any multi token in a call to a
     /// receiver contract.
     ///
     /// Requirements:
@@ -62,35 +99,82 @@ pub trait MultiTokenCore {
     ///   purposes
     /// * Contract MUST panic if called by someone other than token owner or,
     ///   if using Approval Management, one of the approved accounts
-    /// * The receiving contract must implement `ft_on_transfer` according to the
-    ///   standard. If it does not, FT contract's `ft_resolve_transfer` MUST deal
+    /// * The receiving contract must implement `mt_on_transfer` according to the
+    ///   standard. If it does not, MT contract's `mt_resolve_transfer` MUST deal
     ///   with the resulting failed cross-contract call and roll back the transfer.
-    /// * Contract MUST implement the behavior described in `ft_resolve_transfer`
-    /// * `approval_id` is for use with Approval Management extension, see
+    /// * Contract MUST implement the behavior described in `mt_resolve_transfer`
+    /// * `approval` is for use with Approval Management extension, see
     ///   that document for full explanation.
     /// * If using Approval Management, contract MUST nullify approved accounts on
-    ///   successful transfer.
+    ///   successful transfer. Approvals are kept but capped at what the owner has
+    ///   left, whether the owner or an approved account transferred.
     ///
     /// Arguments:
     /// * `receiver_id`: the valid NEAR account receiving the token.
     /// * `token_id`: the token to send.
-    /// * `approval_id`: expected approval ID. A number smaller than
-    ///    2^53, and therefore representable as JSON. See Approval Management
-    ///    standard for full explanation.
+    /// * `amount`: the number of tokens to send.
+    /// * `approval`: the owner and expected approval ID when transferring on
+    ///   behalf of an approved account. An approval naming the caller as owner
+    ///   is ignored, its ID is not checked.
     /// * `memo` (optional): for use cases that may benefit from indexing or
-    ///    providing information for a transfer.
+    ///   providing information for a transfer.
     /// * `msg`: specifies information needed by the receiving contract in
-    ///    order to properly handle the transfer. Can indicate both a function to
-    ///    call and the parameters to pass to that function.
+    ///   order to properly handle the transfer. Can indicate both a function to
+    ///   call and the parameters to pass to that function.
+    ///
+    /// Returns the result of `mt_resolve_transfer`: a one-element list holding the
+    /// amount of the token that stayed with the receiver after any refund.
     fn mt_transfer_call(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
-        approval_id: Option<u64>,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
         memo: Option<String>,
         msg: String,
-    ) -> PromiseOrValue<bool>;
+    ) -> PromiseOrValue<Vec<U128>>;
+
+    /// Batched version of [`mt_transfer_call`](MultiTokenCore::mt_transfer_call).
+    /// The receiver gets a single `mt_on_transfer` call covering all tokens.
+    ///
+    /// Arguments are those of [`mt_batch_transfer`](MultiTokenCore::mt_batch_transfer)
+    /// plus `msg`, as in `mt_transfer_call`.
+    ///
+    /// Returns the amount of each token that stayed with the receiver, in the order
+    /// of `token_ids`.
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+
+    /// Returns the tokens with the given `token_ids`, with `null` in place of
+    /// any token that does not exist.
+    #[must_use]
+    fn mt_token(&self, token_ids: Vec<TokenId>) -> Vec<Option<Token>>;
+
+    /// Returns the balance of `account_id` for `token_id`, `"0"` if the account
+    /// holds none. Never panics: unknown tokens and accounts that are not registered
+    /// (see storage management) read as `"0"` too.
+    #[must_use]
+    fn mt_balance_of(&self, account_id: AccountId, token_id: TokenId) -> U128;
+
+    /// Returns the balances of `account_id` for each of `token_ids`, in the order
+    /// requested, with `"0"` for every token the account doesn't hold, as in
+    /// `mt_balance_of`. Panics if `token_ids` is empty.
+    #[must_use]
+    fn mt_batch_balance_of(&self, account_id: AccountId, token_ids: Vec<TokenId>) -> Vec<U128>;
+
+    /// Returns the total supply of `token_id`, or `null` if no such token.
+    #[must_use]
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128>;
 
-    /// Returns the token with the given `token_id` or `null` if no such token.
-    fn mt_token(&self, token_id: TokenId) -> Option<Token>;
+    /// Returns the total supply of each of `token_ids`, in order, with `null`
+    /// in place of any token that does not exist.
+    #[must_use]
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>>;
 }
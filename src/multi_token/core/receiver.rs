@@ -1,25 +1,50 @@
 use crate::multi_token::token::TokenId;
-use near_sdk::{ext_contract, AccountId, PromiseOrValue};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupSet;
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, require, AccountId, IntoStorageKey, PromiseOrValue};
 
-/// Used when an MT is transferred using `mt_transfer_call`. This trait is implemented on the receiving contract, not on the MT contract.
+/// Used when MTs are transferred using `mt_transfer_call` or `mt_batch_transfer_call`. This trait is implemented on the receiving contract, not on the MT contract.
 #[ext_contract(ext_mt_receiver)]
 pub trait MultiTokenReceiver {
-    /// Take some action after receiving a non-fungible token
+    /// Take some action after receiving multi tokens. Both single and batch
+    /// transfer-calls end up here, a single transfer as a batch of one. Receivers written
+    /// for the earlier single-token version can forward to it with
+    /// [`mt_on_transfer_single`].
     ///
     /// Requirements:
-    /// * Contract MUST restrict calls to this function to a set of whitelisted NFT
+    /// * Contract MUST restrict calls to this function to a set of whitelisted MT
     ///   contracts
     ///
     /// Arguments:
     /// * `sender_id`: the sender of `mt_transfer_call`
-    /// * `previous_owner_id`: the account that owned the MT prior to it being
+    /// * `previous_owner_id`: the account that owned the tokens prior to them being
     ///   transferred to this contract, which can differ from `sender_id` if using
     ///   Approval Management extension
-    /// * `token_id`: the `token_id` argument given to `mt_transfer_call`
+    /// * `token_ids`: the token IDs that were transferred
+    /// * `amounts`: the amount of each token that was transferred
     /// * `msg`: information necessary for this contract to know how to process the
     ///   request. This may include method names and/or arguments.
     ///
-    /// Returns true if token should be returned to `sender_id`
+    /// Returns, for each entry of `token_ids`, the amount of that token that should be
+    /// returned to `previous_owner_id`. `"0"` keeps the whole amount.
+    fn mt_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+}
+
+/// The single-token `mt_on_transfer` [`MultiTokenReceiver`] had before it took batches.
+/// Existing receivers can keep implementing it and answer the batch call through
+/// [`mt_on_transfer_single`].
+#[deprecated(note = "implement the batch `MultiTokenReceiver::mt_on_transfer` instead")]
+pub trait SingleTokenReceiver {
+    /// Take some action after receiving `token_id`. Returns true if the token should be
+    /// returned to `previous_owner_id`.
     fn mt_on_transfer(
         &mut self,
         sender_id: AccountId,
@@ -28,3 +53,185 @@ pub trait MultiTokenReceiver {
         msg: String,
     ) -> PromiseOrValue<bool>;
 }
+
+/// Answers a batch [`MultiTokenReceiver::mt_on_transfer`] through a [`SingleTokenReceiver`],
+/// handing it each token in turn and returning the whole amount of every token it answers
+/// `true` for. A promise can only be returned for a batch of one; its `bool` result is
+/// understood by the MT contract's resolver.
+#[allow(deprecated)]
+pub fn mt_on_transfer_single<R: SingleTokenReceiver>(
+    receiver: &mut R,
+    sender_id: AccountId,
+    previous_owner_id: AccountId,
+    token_ids: Vec<TokenId>,
+    amounts: Vec<U128>,
+    msg: String,
+) -> PromiseOrValue<Vec<U128>> {
+    require!(
+        token_ids.len() == amounts.len(),
+        "token_ids and amounts must have the same length"
+    );
+    let single = token_ids.len() == 1;
+    let mut unused = Vec::with_capacity(amounts.len());
+    for (token_id, amount) in token_ids.into_iter().zip(amounts) {
+        match receiver.mt_on_transfer(
+            sender_id.clone(),
+            previous_owner_id.clone(),
+            token_id,
+            msg.clone(),
+        ) {
+            PromiseOrValue::Value(refund) => unused.push(if refund { amount } else { U128(0) }),
+            PromiseOrValue::Promise(promise) => {
+                require!(single, "A promise can only answer a batch of one token");
+                return PromiseOrValue::Promise(promise);
+            }
+        }
+    }
+    PromiseOrValue::Value(unused)
+}
+
+/// Panics if an incoming batch holds more than `max` tokens. Receivers call it at the top of
+/// `mt_on_transfer` so an oversized batch can't exhaust the gas they need to process it.
+pub fn assert_batch_within(token_ids: &[TokenId], max: usize) {
+    require!(
+        token_ids.len() <= max,
+        format!(
+            "Batch of {} tokens exceeds the maximum of {}",
+            token_ids.len(),
+            max
+        )
+    );
+}
+
+/// Set of MT contracts a receiver accepts `mt_on_transfer` calls from, as required by
+/// [`MultiTokenReceiver`]. Embed it in the receiver contract's state and call
+/// [`assert_allowed`](CallerAllowlist::assert_allowed) with the predecessor at the top of
+/// `mt_on_transfer`. Only `owner_id` may change the list.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CallerAllowlist {
+    pub owner_id: AccountId,
+    allowed: LookupSet<AccountId>,
+}
+
+impl CallerAllowlist {
+    pub fn new<S>(prefix: S, owner_id: AccountId) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self {
+            owner_id,
+            allowed: LookupSet::new(prefix),
+        }
+    }
+
+    pub fn is_allowed(&self, account_id: &AccountId) -> bool {
+        self.allowed.contains(account_id)
+    }
+
+    pub fn assert_allowed(&self, account_id: &AccountId) {
+        require!(self.is_allowed(account_id), "Caller is not allowed");
+    }
+
+    /// Adds `account_id` to the allowlist. Returns `false` if it was already present.
+    pub fn add(&mut self, account_id: &AccountId) -> bool {
+        self.assert_owner();
+        self.allowed.insert(account_id)
+    }
+
+    /// Removes `account_id` from the allowlist. Returns `false` if it was not present.
+    pub fn remove(&mut self, account_id: &AccountId) -> bool {
+        self.assert_owner();
+        self.allowed.remove(account_id)
+    }
+
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only the owner can change the allowlist"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn allowlist() -> CallerAllowlist {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .build());
+        CallerAllowlist::new(b"a", accounts(0))
+    }
+
+    #[allow(deprecated)]
+    struct KeepsGold;
+
+    #[allow(deprecated)]
+    impl SingleTokenReceiver for KeepsGold {
+        fn mt_on_transfer(
+            &mut self,
+            _sender_id: AccountId,
+            _previous_owner_id: AccountId,
+            token_id: TokenId,
+            _msg: String,
+        ) -> PromiseOrValue<bool> {
+            PromiseOrValue::Value(token_id != "gold")
+        }
+    }
+
+    #[test]
+    fn single_receiver_answers_batch() {
+        let unused = mt_on_transfer_single(
+            &mut KeepsGold,
+            accounts(0),
+            accounts(0),
+            vec!["gold".into(), "sword".into()],
+            vec![U128(10), U128(1)],
+            String::new(),
+        );
+        match unused {
+            PromiseOrValue::Value(unused) => assert_eq!(unused, [U128(0), U128(1)]),
+            PromiseOrValue::Promise(_) => panic!("expected a value"),
+        }
+    }
+
+    #[test]
+    fn batch_within_limit() {
+        assert_batch_within(&["0".into(), "1".into()], 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch of 3 tokens exceeds the maximum of 2")]
+    fn batch_over_limit() {
+        assert_batch_within(&["0".into(), "1".into(), "2".into()], 2);
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut allowlist = allowlist();
+        assert!(!allowlist.is_allowed(&accounts(1)));
+        assert!(allowlist.add(&accounts(1)));
+        assert!(!allowlist.add(&accounts(1)));
+        allowlist.assert_allowed(&accounts(1));
+        assert!(allowlist.remove(&accounts(1)));
+        assert!(!allowlist.is_allowed(&accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Caller is not allowed")]
+    fn assert_allowed_rejects_unknown_caller() {
+        allowlist().assert_allowed(&accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can change the allowlist")]
+    fn add_requires_owner() {
+        let mut allowlist = allowlist();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .build());
+        allowlist.add(&accounts(1));
+    }
+}
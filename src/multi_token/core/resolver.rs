@@ -1,41 +1,55 @@
 use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
 use near_sdk::{ext_contract, AccountId};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-/// Used when an MT is transferred using `mt_transfer_call`. This is the method that's called after `mt_on_transfer`. This trait is implemented on the MT contract.
+/// Used when MTs are transferred using `mt_transfer_call` or `mt_batch_transfer_call`. This is the method that's called after `mt_on_transfer`. This trait is implemented on the MT contract.
 #[ext_contract(ext_mt_resolver)]
 pub trait MultiTokenResolver {
-    /// Finalize an `mt_transfer_call` chain of cross-contract calls.
+    /// Finalize an `mt_transfer_call` or `mt_batch_transfer_call` chain of cross-contract calls.
     ///
     /// The `mt_transfer_call` process:
     ///
-    /// 1. Sender calls `mt_transfer_call` on FT contract
-    /// 2. MT contract transfers token from sender to receiver
+    /// 1. Sender calls `mt_transfer_call` on MT contract
+    /// 2. MT contract transfers tokens from sender to receiver
     /// 3. MT contract calls `mt_on_transfer` on receiver contract
     /// 4+. [receiver contract may make other cross-contract calls]
     /// N. MT contract resolves promise chain with `mt_resolve_transfer`, and may
-    ///    transfer token back to sender
+    ///    transfer tokens back to sender
+    ///
+    /// The whole batch is resolved in one call. `mt_on_transfer` returns, per token,
+    /// the amount the receiver wants to give back, so a receiver can keep part of one
+    /// token's amount and return the rest. Each refund is capped at the amount that was
+    /// sent and at what the receiver still holds, since the receiver may have moved
+    /// tokens on before the chain resolved.
     ///
     /// Requirements:
-    /// * Contract MUST forbid calls to this function by any account except self
-    /// * If promise chain failed, contract MUST revert token transfer
-    /// * If promise chain resolves with `true`, contract MUST return token to
-    ///   `sender_id`
+    /// * Contract MUST forbid calls to this function by any account except self, e.g.
+    ///   with [`assert_self`](crate::multi_token::core::assert_self)
+    /// * If promise chain failed, contract MUST revert the transfer of every token
+    /// * If promise chain resolves with amounts to return, contract MUST return those
+    ///   amounts to `previous_owner_id`, and only those
+    /// * Contract MUST give the amount refunded for a token back to the approvals that
+    ///   were spent on it, so a full or partial refund restores them by that much
     ///
     /// Arguments:
     /// * `previous_owner_id`: the owner prior to the call to `mt_transfer_call`
     /// * `receiver_id`: the `receiver_id` argument given to `mt_transfer_call`
-    /// * `token_id`: the `token_id` argument given to `ft_transfer_call`
-    /// * `approvals`: if using Approval Management, contract MUST provide
-    ///   set of original approved accounts in this argument, and restore these
-    ///   approved accounts in case of revert.
+    /// * `token_ids`: the token IDs that were transferred
+    /// * `amounts`: the amount of each token that was transferred
+    /// * `approvals`: if using Approval Management, contract MUST provide, per
+    ///   token, the set of original approved accounts in this argument, and restore
+    ///   these approved accounts in case of revert. A `BTreeMap` is used so that
+    ///   restoration iterates accounts in a deterministic order.
     ///
-    /// Returns true if token was successfully transferred to `receiver_id`.
+    /// Returns the amount of each token that stayed with `receiver_id`, in the order
+    /// of `token_ids`.
     fn mt_resolve_transfer(
         &mut self,
         previous_owner_id: AccountId,
         receiver_id: AccountId,
-        token_id: TokenId,
-        approvals: Option<HashMap<AccountId, u64>>,
-    ) -> bool;
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<BTreeMap<AccountId, u64>>>>,
+    ) -> Vec<U128>;
 }
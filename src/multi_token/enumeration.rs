@@ -0,0 +1,323 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::token::{Token, TokenId};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::U128;
+use near_sdk::{require, AccountId};
+
+/// Page size used when a paginated view is called without a `limit`.
+pub const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// Offers methods helpful in determining account ownership of multi tokens and provides a
+/// way to page through every token of a contract.
+///
+/// `from_index` is the 0-based position to start at; starting at or past the end gives an
+/// empty page. `limit` is the maximum number of tokens to return, [`DEFAULT_PAGE_LIMIT`] if
+/// `None`; `Some(0)` gives an empty page.
+pub trait MultiTokenEnumeration {
+    /// Get a list of all tokens, in the order they were first minted.
+    fn mt_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token>;
+
+    /// Get the number of tokens `mt_tokens` pages through.
+    fn mt_token_count(&self) -> U128;
+
+    /// Get the number of distinct tokens `account_id` holds a non-zero balance of.
+    fn mt_supply_for_owner(&self, account_id: AccountId) -> U128;
+
+    /// Get a list of the tokens `account_id` holds a non-zero balance of. For an account
+    /// holding nothing, only the per-owner index is read.
+    fn mt_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token>;
+}
+
+fn expect_index<T>(index: Option<T>) -> T {
+    if index.is_none() {
+        require!(false, "Enumeration is not enabled");
+    }
+    index.unwrap()
+}
+
+impl MultiToken {
+    fn internal_page(
+        &self,
+        token_ids: &UnorderedSet<TokenId>,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        // Clamped in `u128` so an oversized `from_index` can't wrap around on a narrowing cast.
+        let start = from_index.map_or(0, |from_index| from_index.0);
+        let start = start.min(token_ids.len() as u128) as usize;
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        token_ids
+            .iter()
+            .skip(start)
+            .take(limit)
+            .filter_map(|token_id| self.internal_token(token_id))
+            .collect()
+    }
+
+    /// Cursor-based variant of `mt_tokens_for_owner` that stays stable while tokens are
+    /// minted, burned or moved between page fetches. Tokens come in the order of their
+    /// ids, starting after `cursor`; pass the returned cursor to get the next page, which
+    /// is `None` once there is none. `Some(0)` gives an empty page and hands `cursor`
+    /// back unchanged. Cursors should be treated as opaque.
+    pub fn mt_tokens_for_owner_from_cursor(
+        &self,
+        account_id: AccountId,
+        cursor: Option<String>,
+        limit: Option<u64>,
+    ) -> (Vec<Token>, Option<String>) {
+        let tokens = match expect_index(self.tokens_per_owner.as_ref()).get(&account_id) {
+            Some(tokens) => tokens,
+            None => return (vec![], None),
+        };
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let mut token_ids: Vec<TokenId> = tokens
+            .iter()
+            .filter(|token_id| {
+                cursor
+                    .as_deref()
+                    .is_none_or(|cursor| token_id.as_str() > cursor)
+            })
+            .collect();
+        if limit == 0 {
+            return (vec![], cursor);
+        }
+        token_ids.sort_unstable();
+        let next_cursor = (token_ids.len() > limit).then(|| token_ids[limit - 1].to_string());
+        let page = token_ids
+            .into_iter()
+            .take(limit)
+            .filter_map(|token_id| self.internal_token(token_id))
+            .collect();
+        (page, next_cursor)
+    }
+}
+
+impl MultiTokenEnumeration for MultiToken {
+    fn mt_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.internal_page(expect_index(self.all_token_ids.as_ref()), from_index, limit)
+    }
+
+    fn mt_token_count(&self) -> U128 {
+        U128(expect_index(self.all_token_ids.as_ref()).len() as u128)
+    }
+
+    fn mt_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        expect_index(self.tokens_per_owner.as_ref())
+            .get(&account_id)
+            .map_or(0, |tokens| tokens.len() as u128)
+            .into()
+    }
+
+    fn mt_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        match expect_index(self.tokens_per_owner.as_ref()).get(&account_id) {
+            Some(tokens) if !tokens.is_empty() => self.internal_page(&tokens, from_index, limit),
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::core::MultiTokenCore;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds gold, sword and gem, bob holds gold and shield.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_enumeration();
+        mt.internal_mint(
+            &accounts(0),
+            &["gold".into(), "sword".into(), "gem".into()],
+            &[100, 1, 5],
+            None,
+        );
+        mt.internal_mint(
+            &accounts(1),
+            &["gold".into(), "shield".into()],
+            &[50, 1],
+            None,
+        );
+        mt
+    }
+
+    fn ids(tokens: Vec<Token>) -> Vec<TokenId> {
+        tokens.into_iter().map(|token| token.token_id).collect()
+    }
+
+    #[test]
+    fn mt_tokens_pages_in_mint_order() {
+        let mt = setup();
+        assert_eq!(
+            ids(mt.mt_tokens(None, None)),
+            ["gold", "sword", "gem", "shield"]
+        );
+        assert_eq!(ids(mt.mt_tokens(Some(U128(1)), Some(2))), ["sword", "gem"]);
+        assert_eq!(ids(mt.mt_tokens(Some(U128(3)), Some(2))), ["shield"]);
+        assert!(mt.mt_tokens(Some(U128(4)), None).is_empty());
+    }
+
+    #[test]
+    fn mt_tokens_for_owner_pages_per_account() {
+        let mut mt = setup();
+        assert_eq!(mt.mt_supply_for_owner(accounts(0)), U128(3));
+        assert_eq!(
+            ids(mt.mt_tokens_for_owner(accounts(1), None, None)),
+            ["gold", "shield"]
+        );
+        assert_eq!(
+            ids(mt.mt_tokens_for_owner(accounts(0), Some(U128(1)), Some(1))),
+            ["sword"]
+        );
+
+        mt.mt_transfer(accounts(1), "sword".into(), U128(1), None, None);
+        assert_eq!(mt.mt_supply_for_owner(accounts(0)), U128(2));
+        assert_eq!(
+            ids(mt.mt_tokens_for_owner(accounts(1), None, None)),
+            ["gold", "shield", "sword"]
+        );
+        assert_eq!(mt.mt_tokens(None, None).len(), 4);
+        assert!(mt.mt_tokens_for_owner(accounts(2), None, None).is_empty());
+    }
+
+    #[test]
+    fn empty_account_reads_no_tokens() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "sword".into(), U128(1), None, None);
+        mt.mt_batch_transfer(
+            accounts(1),
+            vec!["gold".into(), "gem".into()],
+            vec![U128(100), U128(5)],
+            None,
+            None,
+        );
+        crate::multi_token::core::take_storage_reads();
+        assert!(mt.mt_tokens_for_owner(accounts(0), None, None).is_empty());
+        assert!(mt.mt_tokens_for_owner(accounts(2), None, None).is_empty());
+        assert_eq!(crate::multi_token::core::take_storage_reads(), 0);
+        assert_eq!(mt.mt_tokens_for_owner(accounts(1), None, None).len(), 4);
+        assert_eq!(crate::multi_token::core::take_storage_reads(), 4);
+    }
+
+    #[test]
+    fn zero_supply_id_is_kept_by_default() {
+        let mut mt = setup();
+        mt.internal_burn(&accounts(1), &["shield".into()], &[1], None);
+        assert_eq!(mt.mt_supply("shield".into()), Some(U128(0)));
+        assert_eq!(mt.mt_token_count(), U128(4));
+        assert_eq!(
+            ids(mt.mt_tokens(None, None)),
+            ["gold", "sword", "gem", "shield"]
+        );
+
+        mt.internal_mint(&accounts(2), &["shield".into()], &[2], None);
+        assert_eq!(mt.mt_supply("shield".into()), Some(U128(2)));
+        assert_eq!(mt.mt_token_count(), U128(4));
+    }
+
+    #[test]
+    fn zero_supply_id_is_removed_when_configured() {
+        let mut mt = setup();
+        mt.remove_on_zero_supply = true;
+        mt.internal_burn(&accounts(0), &["sword".into()], &[1], None);
+        assert_eq!(mt.mt_supply("sword".into()), None);
+        assert_eq!(mt.mt_token_count(), U128(3));
+        assert_eq!(ids(mt.mt_tokens(None, None)), ["gold", "shield", "gem"]);
+
+        // Moving a whole supply is no burn.
+        mt.mt_transfer(accounts(2), "gem".into(), U128(5), None, None);
+        assert_eq!(ids(mt.mt_tokens(None, None)), ["gold", "shield", "gem"]);
+
+        mt.internal_mint(&accounts(2), &["sword".into()], &[1], None);
+        assert_eq!(mt.mt_supply("sword".into()), Some(U128(1)));
+        assert_eq!(mt.mt_token_count(), U128(4));
+        assert_eq!(
+            ids(mt.mt_tokens(None, None)),
+            ["gold", "shield", "gem", "sword"]
+        );
+    }
+
+    #[test]
+    fn cursor_pages_are_stable_under_mints() {
+        let mut mt = setup();
+        let (page, cursor) = mt.mt_tokens_for_owner_from_cursor(accounts(0), None, Some(2));
+        assert_eq!(ids(page), ["gem", "gold"]);
+        assert_eq!(cursor.as_deref(), Some("gold"));
+
+        // One id before the cursor, which is not revisited, and one after it.
+        mt.internal_mint(
+            &accounts(0),
+            &["apple".into(), "silver".into()],
+            &[1, 1],
+            None,
+        );
+        let (page, cursor) = mt.mt_tokens_for_owner_from_cursor(accounts(0), cursor, Some(2));
+        assert_eq!(ids(page), ["silver", "sword"]);
+        assert_eq!(cursor, None);
+
+        let (page, cursor) = mt.mt_tokens_for_owner_from_cursor(accounts(2), None, None);
+        assert!(page.is_empty() && cursor.is_none());
+    }
+
+    #[test]
+    fn page_bounds() {
+        let mt = setup();
+        assert!(mt.mt_tokens(Some(U128(u128::MAX)), None).is_empty());
+        assert!(mt.mt_tokens(None, Some(0)).is_empty());
+        assert!(mt
+            .mt_tokens_for_owner(accounts(0), None, Some(0))
+            .is_empty());
+        assert_eq!(mt.mt_tokens(None, Some(u64::MAX)).len(), 4);
+    }
+
+    #[test]
+    fn zero_limit_differs_from_the_default() {
+        let mt = setup();
+        assert!(mt.mt_tokens(None, Some(0)).is_empty());
+        assert_eq!(mt.mt_tokens(None, None).len(), 4);
+        assert!(mt
+            .mt_tokens_for_owner(accounts(0), None, Some(0))
+            .is_empty());
+        assert!(!mt.mt_tokens_for_owner(accounts(0), None, None).is_empty());
+        let cursor = Some("gold".to_string());
+        let (page, next_cursor) =
+            mt.mt_tokens_for_owner_from_cursor(accounts(0), cursor.clone(), Some(0));
+        assert!(page.is_empty());
+        assert_eq!(next_cursor, cursor);
+        let (page, _) = mt.mt_tokens_for_owner_from_cursor(accounts(0), cursor, None);
+        assert!(!page.is_empty());
+    }
+
+    #[test]
+    fn oversized_from_index_gives_an_empty_page() {
+        let mt = setup();
+        for from_index in [u64::MAX as u128 + 1, u128::MAX] {
+            assert!(mt.mt_tokens(Some(U128(from_index)), Some(10)).is_empty());
+            assert!(mt
+                .mt_tokens_for_owner(accounts(0), Some(U128(from_index)), Some(10))
+                .is_empty());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Enumeration is not enabled")]
+    fn requires_enumeration() {
+        MultiToken::new(b"m").mt_tokens(None, None);
+    }
+}
@@ -0,0 +1,160 @@
+use near_sdk::env;
+
+/// Errors of the core transfer, mint, burn and approval logic of
+/// [`MultiToken`](crate::multi_token::core::MultiToken). The `try_` methods return them, and
+/// every panicking method aborts with their `Display` message through [`abort`], so a given
+/// failure reads the same whichever way it is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MtError {
+    /// A batch lists no tokens at all.
+    NoTokenIds,
+    /// A batch lists more tokens than allowed.
+    BatchTooLarge,
+    /// An amount to mint, burn or transfer is 0.
+    ZeroAmount,
+    /// `token_ids` and `amounts` differ in length.
+    LengthMismatch,
+    /// `token_ids` and `approvals` differ in length.
+    ApprovalsLengthMismatch,
+    /// A batch lists a token more than once, which [`DuplicateTokenIds::Reject`] forbids.
+    ///
+    /// [`DuplicateTokenIds::Reject`]: crate::multi_token::core::DuplicateTokenIds::Reject
+    DuplicateTokenId,
+    /// The approvals of a batch name different owners.
+    MixedOwners,
+    /// The tokens would go back to the account they come from.
+    SelfTransfer,
+    /// With storage management, the receiver is not registered.
+    ReceiverNotRegistered,
+    /// The owner holds less than the amount to withdraw.
+    InsufficientBalance,
+    /// The owner holds enough, but only counting what is locked in escrow.
+    InsufficientUnlockedBalance,
+    /// A balance would exceed `u128::MAX`.
+    BalanceOverflow,
+    /// A total supply would exceed `u128::MAX`.
+    TotalSupplyOverflow,
+    /// A total supply would drop below 0, which means the balances are corrupt.
+    TotalSupplyUnderflow,
+    /// An approval was given but approval management is not enabled.
+    ApprovalNotSupported,
+    /// The sender has no approval from the owner for the token.
+    NotApproved,
+    /// The sender's approval has another ID than the one given.
+    InvalidApprovalId,
+    /// The sender's approval covers less than the amount to transfer.
+    ExceedsApprovedAmount,
+    /// The contract is paused.
+    Paused,
+}
+
+impl std::fmt::Display for MtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MtError::NoTokenIds => "No token_ids provided",
+            MtError::BatchTooLarge => "Batch exceeds the maximum size",
+            MtError::ZeroAmount => "The amount should be a positive number",
+            MtError::LengthMismatch => "token_ids and amounts must have the same length",
+            MtError::ApprovalsLengthMismatch => "token_ids and approvals must have the same length",
+            MtError::DuplicateTokenId => "Duplicate token_id in batch",
+            MtError::MixedOwners => "All tokens of a batch must have the same owner",
+            MtError::SelfTransfer => "Sender and receiver should be different",
+            MtError::ReceiverNotRegistered => "Receiver not registered",
+            MtError::InsufficientBalance => "The account doesn't have enough balance",
+            MtError::InsufficientUnlockedBalance => "Insufficient unlocked balance",
+            MtError::BalanceOverflow => "Balance overflow",
+            MtError::TotalSupplyOverflow => "Total supply overflow",
+            MtError::TotalSupplyUnderflow => "Total supply underflow",
+            MtError::ApprovalNotSupported => "Approval Management is not supported",
+            MtError::NotApproved => "Sender is not approved",
+            MtError::InvalidApprovalId => "Invalid approval ID",
+            MtError::ExceedsApprovedAmount => "Transfer amount exceeds the approved amount",
+            MtError::Paused => "Contract is paused",
+        })
+    }
+}
+
+impl std::error::Error for MtError {}
+
+/// Aborts the call with the message of `e`. Like `require!`, it panics normally in debug
+/// builds, so unit tests can catch the message, and goes through `env::panic_str` on chain.
+pub fn abort(e: MtError) -> ! {
+    if cfg!(debug_assertions) {
+        panic!("{}", e)
+    } else {
+        env::panic_str(&e.to_string())
+    }
+}
+
+/// Aborts with the error of `result`, if any, and returns its value otherwise.
+pub(crate) fn unwrap_or_abort<T>(result: Result<T, MtError>) -> T {
+    result.unwrap_or_else(|e| abort(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MtError;
+
+    #[test]
+    fn display_matches_the_panic_messages() {
+        let messages = [
+            (MtError::NoTokenIds, "No token_ids provided"),
+            (MtError::BatchTooLarge, "Batch exceeds the maximum size"),
+            (
+                MtError::ZeroAmount,
+                "The amount should be a positive number",
+            ),
+            (
+                MtError::LengthMismatch,
+                "token_ids and amounts must have the same length",
+            ),
+            (
+                MtError::ApprovalsLengthMismatch,
+                "token_ids and approvals must have the same length",
+            ),
+            (MtError::DuplicateTokenId, "Duplicate token_id in batch"),
+            (
+                MtError::MixedOwners,
+                "All tokens of a batch must have the same owner",
+            ),
+            (
+                MtError::SelfTransfer,
+                "Sender and receiver should be different",
+            ),
+            (MtError::ReceiverNotRegistered, "Receiver not registered"),
+            (
+                MtError::InsufficientBalance,
+                "The account doesn't have enough balance",
+            ),
+            (
+                MtError::InsufficientUnlockedBalance,
+                "Insufficient unlocked balance",
+            ),
+            (MtError::BalanceOverflow, "Balance overflow"),
+            (MtError::TotalSupplyOverflow, "Total supply overflow"),
+            (MtError::TotalSupplyUnderflow, "Total supply underflow"),
+            (
+                MtError::ApprovalNotSupported,
+                "Approval Management is not supported",
+            ),
+            (MtError::NotApproved, "Sender is not approved"),
+            (MtError::InvalidApprovalId, "Invalid approval ID"),
+            (
+                MtError::ExceedsApprovedAmount,
+                "Transfer amount exceeds the approved amount",
+            ),
+            (MtError::Paused, "Contract is paused"),
+        ];
+        for (e, message) in messages {
+            assert_eq!(e.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn works_with_question_mark() {
+        fn fails() -> Result<(), Box<dyn std::error::Error>> {
+            Err(MtError::Paused)?
+        }
+        assert_eq!(fails().unwrap_err().to_string(), "Contract is paused");
+    }
+}
@@ -0,0 +1,268 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::error::{abort, MtError};
+#[cfg(feature = "events")]
+use crate::multi_token::events::MtTransfer;
+use crate::multi_token::token::TokenId;
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{env, require, AccountId, Balance};
+
+fn expect_locks(
+    locked_balances: Option<&mut LookupMap<(AccountId, TokenId), Balance>>,
+) -> &mut LookupMap<(AccountId, TokenId), Balance> {
+    if locked_balances.is_none() {
+        require!(false, "Escrow locks are not enabled");
+    }
+    locked_balances.unwrap()
+}
+
+impl MultiToken {
+    /// Returns how much of `token_id` is locked for `account_id`, `"0"` if nothing is or
+    /// escrow locks are not enabled.
+    pub fn mt_locked_balance_of(&self, account_id: AccountId, token_id: TokenId) -> U128 {
+        self.internal_locked_balance_of(&account_id, &token_id)
+            .into()
+    }
+
+    pub fn internal_locked_balance_of(
+        &self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+    ) -> Balance {
+        self.locked_balances
+            .as_ref()
+            .and_then(|locked_balances| {
+                locked_balances.get(&(account_id.clone(), token_id.clone()))
+            })
+            .unwrap_or(0)
+    }
+
+    /// The error for spending `amount` of `token_id` when `owner_id` holds only `balance` of
+    /// it: [`MtError::InsufficientUnlockedBalance`] if the locked amount would cover the
+    /// rest, so callers can tell that the tokens are there but in escrow.
+    pub(crate) fn internal_insufficient_balance(
+        &self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+        amount: Balance,
+    ) -> MtError {
+        let locked = self.internal_locked_balance_of(owner_id, token_id);
+        if locked > 0 && balance.saturating_add(locked) >= amount {
+            MtError::InsufficientUnlockedBalance
+        } else {
+            MtError::InsufficientBalance
+        }
+    }
+
+    /// Moves `amount` of `owner_id`'s `token_id` out of its spendable balance into escrow.
+    /// Locked tokens don't show in `mt_balance_of`, can't be transferred or burned, and cap
+    /// the owner's approvals like any other drop in balance. A token whose whole balance is
+    /// locked is no longer listed for the owner by enumeration, and storage management
+    /// doesn't see it when the account unregisters. Access control is up to the contract.
+    pub fn internal_lock(&mut self, owner_id: &AccountId, token_id: &TokenId, amount: Balance) {
+        if amount == 0 {
+            abort(MtError::ZeroAmount);
+        }
+        let initial_storage_usage = env::storage_usage();
+        let balance = self.internal_balance_of(owner_id, token_id);
+        if balance < amount {
+            abort(MtError::InsufficientBalance);
+        }
+        let key = (owner_id.clone(), token_id.clone());
+        let locked_balances = expect_locks(self.locked_balances.as_mut());
+        let locked = locked_balances.get(&key).unwrap_or(0);
+        // Can't overflow, the locked and the spendable amount together are part of the supply.
+        locked_balances.insert(&key, &(locked + amount));
+        self.internal_write_balance(owner_id, token_id, balance, balance - amount);
+        self.internal_cap_approvals(owner_id, token_id);
+        self.internal_track_storage(owner_id, initial_storage_usage);
+    }
+
+    /// Moves `amount` of `owner_id`'s locked `token_id` back into its spendable balance.
+    /// Access control is up to the contract.
+    pub fn internal_unlock(&mut self, owner_id: &AccountId, token_id: &TokenId, amount: Balance) {
+        if amount == 0 {
+            abort(MtError::ZeroAmount);
+        }
+        let initial_storage_usage = env::storage_usage();
+        self.internal_take_locked(owner_id, token_id, amount);
+        let balance = self.internal_balance_of(owner_id, token_id);
+        self.internal_write_balance(owner_id, token_id, balance, balance + amount);
+        self.internal_track_storage(owner_id, initial_storage_usage);
+    }
+
+    /// Releases `amount` of `owner_id`'s locked `token_id` to `receiver_id`, e.g. the buyer
+    /// of an escrowed sale, and emits an `MtTransfer` event from the owner to the receiver
+    /// with the memo `"escrow_release"`. Access control is up to the contract.
+    pub fn internal_release(
+        &mut self,
+        owner_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+    ) {
+        if amount == 0 {
+            abort(MtError::ZeroAmount);
+        }
+        if owner_id == receiver_id {
+            abort(MtError::SelfTransfer);
+        }
+        if !self.internal_can_hold_tokens(receiver_id) {
+            abort(MtError::ReceiverNotRegistered);
+        }
+        let balance = self.internal_balance_of(receiver_id, token_id);
+        let new_balance = match balance.checked_add(amount) {
+            Some(new_balance) => new_balance,
+            None => abort(MtError::BalanceOverflow),
+        };
+        let initial_storage_usage = env::storage_usage();
+        self.internal_take_locked(owner_id, token_id, amount);
+        self.internal_track_storage(owner_id, initial_storage_usage);
+        let initial_storage_usage = env::storage_usage();
+        self.internal_write_balance(receiver_id, token_id, balance, new_balance);
+        self.internal_track_storage(receiver_id, initial_storage_usage);
+        self.internal_touch(token_id);
+        self.internal_record_volume(token_id, amount);
+        #[cfg(feature = "events")]
+        MtTransfer::builder(owner_id, receiver_id, &[token_id.as_str()])
+            .amounts(&[&amount.to_string()])
+            .memo("escrow_release")
+            .emit();
+    }
+
+    /// Removes `amount` from the locked balance of `owner_id` for `token_id`, without
+    /// putting it back anywhere.
+    fn internal_take_locked(&mut self, owner_id: &AccountId, token_id: &TokenId, amount: Balance) {
+        let key = (owner_id.clone(), token_id.clone());
+        let locked_balances = expect_locks(self.locked_balances.as_mut());
+        let locked = locked_balances.get(&key).unwrap_or(0);
+        require!(
+            locked >= amount,
+            "The account doesn't have enough locked balance"
+        );
+        if locked == amount {
+            locked_balances.remove(&key);
+        } else {
+            locked_balances.insert(&key, &(locked - amount));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::approval::MultiTokenApproval;
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds 100 gold, 40 of them locked, and approved bob for 80.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_approvals().with_locks();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        mt.mt_approve(vec!["gold".into()], vec![U128(80)], accounts(1), None);
+        mt.internal_lock(&accounts(0), &"gold".into(), 40);
+        mt
+    }
+
+    #[test]
+    fn lock_moves_balance_aside() {
+        let mt = setup();
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(60));
+        assert_eq!(
+            mt.mt_locked_balance_of(accounts(0), "gold".into()),
+            U128(40)
+        );
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        assert!(!mt.mt_is_approved(
+            vec!["gold".into()],
+            accounts(0),
+            accounts(1),
+            vec![U128(61)],
+            None
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient unlocked balance")]
+    fn locked_amount_cannot_be_spent() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(2), "gold".into(), U128(61), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient unlocked balance")]
+    fn batch_cannot_dip_into_the_locked_amount() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(0), &["silver".into()], &[10], None);
+        mt.mt_batch_transfer(
+            accounts(2),
+            vec!["silver".into(), "gold".into(), "gold".into()],
+            vec![U128(10), U128(30), U128(31)],
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn spending_more_than_held_is_not_about_locks() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(2), "gold".into(), U128(101), None, None);
+    }
+
+    #[test]
+    fn unlock_restores_balance() {
+        let mut mt = setup();
+        mt.internal_unlock(&accounts(0), &"gold".into(), 40);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(mt.mt_locked_balance_of(accounts(0), "gold".into()), U128(0));
+        mt.mt_transfer(accounts(2), "gold".into(), U128(100), None, None);
+        assert_eq!(mt.mt_balance_of(accounts(2), "gold".into()), U128(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough locked balance")]
+    fn unlock_more_than_locked_fails() {
+        let mut mt = setup();
+        mt.internal_unlock(&accounts(0), &"gold".into(), 41);
+    }
+
+    #[test]
+    fn release_pays_the_receiver() {
+        let mut mt = setup();
+        mt.internal_release(&accounts(0), &accounts(2), &"gold".into(), 30);
+        assert_eq!(mt.mt_balance_of(accounts(2), "gold".into()), U128(30));
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(60));
+        assert_eq!(
+            mt.mt_locked_balance_of(accounts(0), "gold".into()),
+            U128(10)
+        );
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(100)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"charlie","token_ids":["gold"],"amounts":["30"],"memo":"escrow_release"}]}"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough locked balance")]
+    fn release_is_limited_to_the_locked_amount() {
+        let mut mt = setup();
+        mt.internal_release(&accounts(0), &accounts(2), &"gold".into(), 41);
+    }
+
+    #[test]
+    #[should_panic(expected = "Escrow locks are not enabled")]
+    fn lock_requires_the_extension() {
+        let mut mt = setup();
+        mt.locked_balances = None;
+        mt.internal_lock(&accounts(0), &"gold".into(), 1);
+    }
+}
@@ -6,21 +6,66 @@
 //! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
 //!
 //! The three events in this standard are [`MtMint`], [`MtTransfer`], and [`MtBurn`].
+//! [`MtApprove`] extends them to log approvals granted with `mt_approve`, and
+//! [`MtRegister`] and [`MtUnregister`] to log accounts entering and leaving storage
+//! management. [`MtPause`] and [`MtUnpause`] log the contract-wide pause switch.
 //!
 //! These events can be logged by calling `.emit()` on them if a single event, or calling
 //! [`MtMint::emit_many`], [`MtTransfer::emit_many`],
-//! or [`MtBurn::emit_many`] respectively.
+//! or [`MtBurn::emit_many`] respectively. Malformed events make `emit` panic; use the
+//! `try_emit`/`try_emit_many` variants to get an [`EventError`] instead.
+//!
+//! To avoid spelling out optional fields, events can also be built with
+//! [`MtMint::builder`], [`MtTransfer::builder`] and [`MtBurn::builder`].
+//!
+//! For non-NEAR consumers the event structs can also be rendered with camelCase keys via
+//! `to_camel_case_json`; this never affects what `.emit()` logs.
+//!
+//! Off-chain indexers can parse logged events back with [`parse_event`], which yields the
+//! owned mirror types [`MtMintOwned`], [`MtTransferOwned`], [`MtBurnOwned`],
+//! [`MtApproveOwned`] and [`MtAccountOwned`].
+//!
+//! [`MtTransfer::emit_many`] splits a transfer event whose log line would exceed
+//! [`MAX_EVENT_LOG_LEN`] into several valid events. Large mints can be spread over
+//! several events with [`MtMint::emit_chunked`].
+//!
+//! Transfers can additionally be tagged for off-chain relayers with
+//! [`MtTransfer::emit_many_with_hook`], which logs a separate [`HOOK_LOG_PREFIX`] line
+//! after the standard event.
 
 use crate::event::NearEvent;
-use near_sdk::AccountId;
-use serde::Serialize;
+use near_sdk::{env, require, AccountId};
+use serde::{Deserialize, Serialize};
+
+/// Events schema version written by `emit`/`emit_many`. Use the `emit_many_versioned`
+/// methods to emit under a different version.
+pub(crate) const MT_EVENT_VERSION: &str = "1.0.0";
+
+/// Longest `EVENT_JSON:` line, in bytes, [`MtTransfer::emit_many`] writes before splitting
+/// the event into several lines. Note that the runtime limits the total size of a receipt's
+/// logs, not of each line, so splitting keeps lines manageable for indexers but does not let
+/// a receipt log more overall.
+pub const MAX_EVENT_LOG_LEN: usize = 4096;
+
+/// Suggested `max_ids_per_event` for [`MtMint::emit_chunked`].
+pub const DEFAULT_MAX_IDS_PER_EVENT: usize = 100;
+
+/// Prefix of the log line written by [`MtTransfer::emit_many_with_hook`].
+/// It is deliberately distinct from `EVENT_JSON:` so indexers ignore it.
+pub const HOOK_LOG_PREFIX: &str = "MT_HOOK:";
 
 /// Data to log for an MT mint event. To log this event, call [`.emit()`](MtMint::emit).
+///
+/// `token_ids` can be any string-like slice, such as `&[String]`; such entries are logged
+/// with [`MtMint::emit_many_from`].
 #[must_use]
 #[derive(Serialize, Debug, Clone)]
-pub struct MtMint<'a> {
+pub struct MtMint<'a, S = &'a str> {
     pub owner_id: &'a AccountId,
-    pub token_ids: &'a [&'a str],
+    pub token_ids: &'a [S],
+    /// Account that minted on behalf of `owner_id`, such as a marketplace operator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<&'a AccountId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<&'a str>,
 }
@@ -32,25 +77,149 @@ impl MtMint<'_> {
         Self::emit_many(&[self])
     }
 
+    /// Same as [`emit`](MtMint::emit), but serializes into `buffer` through
+    /// [`NearEvent::emit_with_buffer`], so a contract emitting one event per token can
+    /// reuse the same allocation.
+    pub fn emit_single(self, buffer: &mut String) {
+        expect_valid(validate(self.token_ids, None, self.memo));
+        new_246(MT_EVENT_VERSION, Nep246EventKind::MtMint(&[self])).emit_with_buffer(buffer)
+    }
+
     /// Emits an mt mint event, through [`env::log_str`](near_sdk::env::log_str),
     /// where each [`MtMint`] represents the data of each mint.
+    ///
+    /// Panics if [`try_emit_many`](MtMint::try_emit_many) would return an error.
     pub fn emit_many(data: &[MtMint<'_>]) {
-        new_246_v1(Nep246EventKind::MtMint(data)).emit()
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Same as [`emit_many`](MtMint::emit_many), for entries whose `token_ids` are of
+    /// another string-like type. Logs exactly what the `&str` form would.
+    pub fn emit_many_from<S: AsRef<str>>(data: &[MtMint<'_, S>]) {
+        let token_ids = as_strs(data.iter().map(|d| d.token_ids));
+        let data: Vec<MtMint> = data
+            .iter()
+            .zip(&token_ids)
+            .map(|(d, token_ids)| MtMint {
+                owner_id: d.owner_id,
+                token_ids,
+                authorized_id: d.authorized_id,
+                memo: d.memo,
+            })
+            .collect();
+        Self::emit_many(&data)
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    pub fn try_emit_many(data: &[MtMint<'_>]) -> Result<(), EventError> {
+        Self::try_emit_many_versioned(data, MT_EVENT_VERSION)
+    }
+
+    /// Same as [`emit_many`](MtMint::emit_many), but writes `version` into the event's
+    /// `version` field instead of `"1.0.0"`, e.g. to pin a newer events schema.
+    pub fn emit_many_versioned(data: &[MtMint<'_>], version: &'static str) {
+        expect_valid(Self::try_emit_many_versioned(data, version))
+    }
+
+    /// Fallible form of [`emit_many_versioned`](MtMint::emit_many_versioned).
+    pub fn try_emit_many_versioned(
+        data: &[MtMint<'_>],
+        version: &'static str,
+    ) -> Result<(), EventError> {
+        Self::to_near_event(data, version).map(NearEvent::emit)
+    }
+
+    /// Same as [`emit_many`](MtMint::emit_many), but logs `data` as several events holding
+    /// at most `max_ids_per_event` token ids each, splitting the ids of an entry across
+    /// events if needed. Keeps the lines of large mints small, e.g. with
+    /// [`DEFAULT_MAX_IDS_PER_EVENT`]. Nothing is logged if any entry is malformed.
+    pub fn emit_chunked(data: &[MtMint<'_>], max_ids_per_event: usize) {
+        require!(
+            max_ids_per_event > 0,
+            "max_ids_per_event must be a positive number"
+        );
+        expect_valid(
+            data.iter()
+                .try_for_each(|d| validate(d.token_ids, None, d.memo)),
+        );
+        let mut chunk = Vec::new();
+        let mut ids_in_chunk = 0;
+        for mint in data {
+            let mut token_ids = mint.token_ids;
+            while !token_ids.is_empty() {
+                let taken = token_ids.len().min(max_ids_per_event - ids_in_chunk);
+                let (head, rest) = token_ids.split_at(taken);
+                chunk.push(MtMint {
+                    token_ids: head,
+                    ..mint.clone()
+                });
+                ids_in_chunk += taken;
+                token_ids = rest;
+                if ids_in_chunk == max_ids_per_event {
+                    new_246(MT_EVENT_VERSION, Nep246EventKind::MtMint(&chunk)).emit();
+                    chunk.clear();
+                    ids_in_chunk = 0;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            new_246(MT_EVENT_VERSION, Nep246EventKind::MtMint(&chunk)).emit();
+        }
+    }
+
+    /// Validates `data` and wraps it into a [`NearEvent`] under `version` without logging
+    /// it, e.g. to forward its JSON through another channel.
+    pub fn to_near_event<'a>(
+        data: &'a [MtMint<'a>],
+        version: &'static str,
+    ) -> Result<NearEvent<'a>, EventError> {
+        data.iter()
+            .try_for_each(|d| validate(d.token_ids, None, d.memo))?;
+        Ok(new_246(version, Nep246EventKind::MtMint(data)))
+    }
+
+    /// Serializes this event data with camelCase keys, for off-chain consumers such as
+    /// API responses. Keys are emitted in alphabetical order. Events logged on chain
+    /// always use snake_case.
+    pub fn to_camel_case_json(&self) -> String {
+        to_camel_case_json(self)
     }
 }
 
 /// Data to log for an MT transfer event. To log this event,
 /// call [`.emit()`](MtTransfer::emit).
+///
+/// `token_ids` can be any string-like slice, such as `&[String]`; such entries are logged
+/// with [`MtTransfer::emit_many_from`].
 #[must_use]
 #[derive(Serialize, Debug, Clone)]
-pub struct MtTransfer<'a> {
+pub struct MtTransfer<'a, S = &'a str> {
     pub old_owner_id: &'a AccountId,
     pub new_owner_id: &'a AccountId,
-    pub token_ids: &'a [&'a str],
+    pub token_ids: &'a [S],
+    /// Amount moved for each entry of `token_ids`, as decimal `U128` strings.
+    /// Must have the same length as `token_ids`.
+    pub amounts: &'a [&'a str],
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authorized_id: Option<&'a AccountId>,
+    /// Approval under which `authorized_id` moved each entry of `token_ids`.
+    /// Must have the same length as `token_ids`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_ids: Option<&'a [u64]>,
+    /// Memo of the whole entry. Must not be set together with `memos`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<&'a str>,
+    /// Memo of each entry of `token_ids`, logged as an array with `null` for tokens
+    /// without one. Must have the same length as `token_ids` and must not be set together
+    /// with `memo`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memos: Option<&'a [Option<&'a str>]>,
 }
 
 impl MtTransfer<'_> {
@@ -62,17 +231,200 @@ impl MtTransfer<'_> {
 
     /// Emits an mt transfer event, through [`env::log_str`](near_sdk::env::log_str),
     /// where each [`MtTransfer`] represents the data of each transfer.
+    ///
+    /// Panics if [`try_emit_many`](MtTransfer::try_emit_many) would return an error.
     pub fn emit_many(data: &[MtTransfer<'_>]) {
-        new_246_v1(Nep246EventKind::MtTransfer(data)).emit()
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Same as [`emit_many`](MtTransfer::emit_many), for entries whose `token_ids` are of
+    /// another string-like type. Logs exactly what the `&str` form would.
+    pub fn emit_many_from<S: AsRef<str>>(data: &[MtTransfer<'_, S>]) {
+        let token_ids = as_strs(data.iter().map(|d| d.token_ids));
+        let data: Vec<MtTransfer> = data
+            .iter()
+            .zip(&token_ids)
+            .map(|(d, token_ids)| MtTransfer {
+                old_owner_id: d.old_owner_id,
+                new_owner_id: d.new_owner_id,
+                token_ids,
+                amounts: d.amounts,
+                authorized_id: d.authorized_id,
+                approval_ids: d.approval_ids,
+                memo: d.memo,
+                memos: d.memos,
+            })
+            .collect();
+        Self::emit_many(&data)
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    ///
+    /// If the event's line would be longer than [`MAX_EVENT_LOG_LEN`], it is split into
+    /// several events instead, dividing `data` and, if needed, the `token_ids`, `amounts`
+    /// and `approval_ids` and `memos` of a single entry. Together the lines carry every id exactly once.
+    pub fn try_emit_many(data: &[MtTransfer<'_>]) -> Result<(), EventError> {
+        Self::try_emit_many_versioned(data, MT_EVENT_VERSION)
+    }
+
+    /// Same as [`emit_many`](MtTransfer::emit_many), but writes `version` into the event's
+    /// `version` field instead of `"1.0.0"`, e.g. to pin a newer events schema.
+    pub fn emit_many_versioned(data: &[MtTransfer<'_>], version: &'static str) {
+        expect_valid(Self::try_emit_many_versioned(data, version))
+    }
+
+    /// Fallible form of [`emit_many_versioned`](MtTransfer::emit_many_versioned).
+    pub fn try_emit_many_versioned(
+        data: &[MtTransfer<'_>],
+        version: &'static str,
+    ) -> Result<(), EventError> {
+        data.iter().try_for_each(MtTransfer::validate)?;
+        Self::emit_split(data, version);
+        Ok(())
+    }
+
+    /// Logs already validated `data`, halving it until each line fits [`MAX_EVENT_LOG_LEN`]
+    /// or holds a single token id.
+    fn emit_split(data: &[MtTransfer<'_>], version: &'static str) {
+        let event = new_246(version, Nep246EventKind::MtTransfer(data));
+        let line = event.to_event_json_string();
+        if line.len() <= MAX_EVENT_LOG_LEN {
+            return event.emit_line(&line);
+        }
+        match data {
+            [transfer] if transfer.token_ids.len() > 1 => {
+                let mid = transfer.token_ids.len() / 2;
+                let (left_ids, right_ids) = transfer.token_ids.split_at(mid);
+                let (left_amounts, right_amounts) = transfer.amounts.split_at(mid);
+                let (left_approval_ids, right_approval_ids) =
+                    split_option(transfer.approval_ids, mid);
+                let (left_memos, right_memos) = split_option(transfer.memos, mid);
+                let left = MtTransfer {
+                    token_ids: left_ids,
+                    amounts: left_amounts,
+                    approval_ids: left_approval_ids,
+                    memos: left_memos,
+                    ..transfer.clone()
+                };
+                let right = MtTransfer {
+                    token_ids: right_ids,
+                    amounts: right_amounts,
+                    approval_ids: right_approval_ids,
+                    memos: right_memos,
+                    ..transfer.clone()
+                };
+                Self::emit_split(&[left], version);
+                Self::emit_split(&[right], version);
+            }
+            [_] => event.emit_line(&line),
+            _ => {
+                let (left, right) = data.split_at(data.len() / 2);
+                Self::emit_split(left, version);
+                Self::emit_split(right, version);
+            }
+        }
+    }
+
+    /// Validates `data` and wraps it into a [`NearEvent`] under `version` without logging
+    /// it, e.g. to forward its JSON through another channel.
+    pub fn to_near_event<'a>(
+        data: &'a [MtTransfer<'a>],
+        version: &'static str,
+    ) -> Result<NearEvent<'a>, EventError> {
+        data.iter().try_for_each(MtTransfer::validate)?;
+        Ok(new_246(version, Nep246EventKind::MtTransfer(data)))
+    }
+
+    fn validate(&self) -> Result<(), EventError> {
+        validate(self.token_ids, Some(self.amounts), self.memo)?;
+        if let Some(approval_ids) = self.approval_ids {
+            if approval_ids.len() != self.token_ids.len() {
+                return Err(EventError::ApprovalIdsLengthMismatch {
+                    token_ids: self.token_ids.len(),
+                    approval_ids: approval_ids.len(),
+                });
+            }
+        }
+        if let Some(memos) = self.memos {
+            if self.memo.is_some() {
+                return Err(EventError::MemoAndMemos);
+            }
+            if memos.len() != self.token_ids.len() {
+                return Err(EventError::MemosLengthMismatch {
+                    token_ids: self.token_ids.len(),
+                    memos: memos.len(),
+                });
+            }
+            memos.iter().copied().try_for_each(validate_memo)?;
+        }
+        Ok(())
+    }
+
+    /// Serializes this event data with camelCase keys, for off-chain consumers such as
+    /// API responses. Keys are emitted in alphabetical order. Events logged on chain
+    /// always use snake_case.
+    pub fn to_camel_case_json(&self) -> String {
+        to_camel_case_json(self)
+    }
+
+    /// Same as [`emit`](MtTransfer::emit), but also logs a hook line when `hook_tag` is set.
+    pub fn emit_with_hook(self, hook_tag: Option<&str>) {
+        Self::emit_many_with_hook(&[self], hook_tag)
+    }
+
+    /// Emits the standard transfer event and, if `hook_tag` is set, a second log line
+    /// prefixed with [`HOOK_LOG_PREFIX`] carrying the tag for downstream relayers.
+    pub fn emit_many_with_hook(data: &[MtTransfer<'_>], hook_tag: Option<&str>) {
+        Self::emit_many(data);
+        if let Some(hook_tag) = hook_tag {
+            MtHook {
+                hook_tag,
+                event: Nep246EventKind::MtTransfer(data).event_name(),
+            }
+            .log()
+        }
+    }
+}
+
+fn split_option<T>(slice: Option<&[T]>, mid: usize) -> (Option<&[T]>, Option<&[T]>) {
+    match slice.map(|slice| slice.split_at(mid)) {
+        Some((left, right)) => (Some(left), Some(right)),
+        None => (None, None),
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct MtHook<'a> {
+    hook_tag: &'a str,
+    event: &'static str,
+}
+
+impl MtHook<'_> {
+    fn log(&self) {
+        // Hooks cannot fail to serialize so fine to panic on error
+        #[allow(clippy::redundant_closure)]
+        let json = serde_json::to_string(self)
+            .ok()
+            .unwrap_or_else(|| env::abort());
+        env::log_str(&format!("{}{}", HOOK_LOG_PREFIX, json));
     }
 }
 
 /// Data to log for an MT burn event. To log this event, call [`.emit()`](MtBurn::emit).
+///
+/// `token_ids` can be any string-like slice, such as `&[String]`; such entries are logged
+/// with [`MtBurn::emit_many_from`].
 #[must_use]
 #[derive(Serialize, Debug, Clone)]
-pub struct MtBurn<'a> {
+pub struct MtBurn<'a, S = &'a str> {
     pub owner_id: &'a AccountId,
-    pub token_ids: &'a [&'a str],
+    pub token_ids: &'a [S],
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authorized_id: Option<&'a AccountId>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -88,59 +440,838 @@ impl MtBurn<'_> {
 
     /// Emits an Mt burn event, through [`env::log_str`](near_sdk::env::log_str),
     /// where each [`MtBurn`] represents the data of each burn.
+    ///
+    /// Panics if [`try_emit_many`](MtBurn::try_emit_many) would return an error.
     pub fn emit_many<'a>(data: &'a [MtBurn<'a>]) {
-        new_246_v1(Nep246EventKind::MtBurn(data)).emit()
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Same as [`emit_many`](MtBurn::emit_many), for entries whose `token_ids` are of
+    /// another string-like type. Logs exactly what the `&str` form would.
+    pub fn emit_many_from<S: AsRef<str>>(data: &[MtBurn<'_, S>]) {
+        let token_ids = as_strs(data.iter().map(|d| d.token_ids));
+        let data: Vec<MtBurn> = data
+            .iter()
+            .zip(&token_ids)
+            .map(|(d, token_ids)| MtBurn {
+                owner_id: d.owner_id,
+                token_ids,
+                authorized_id: d.authorized_id,
+                memo: d.memo,
+            })
+            .collect();
+        Self::emit_many(&data)
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    pub fn try_emit_many<'a>(data: &'a [MtBurn<'a>]) -> Result<(), EventError> {
+        Self::try_emit_many_versioned(data, MT_EVENT_VERSION)
+    }
+
+    /// Same as [`emit_many`](MtBurn::emit_many), but writes `version` into the event's
+    /// `version` field instead of `"1.0.0"`, e.g. to pin a newer events schema.
+    pub fn emit_many_versioned<'a>(data: &'a [MtBurn<'a>], version: &'static str) {
+        expect_valid(Self::try_emit_many_versioned(data, version))
+    }
+
+    /// Fallible form of [`emit_many_versioned`](MtBurn::emit_many_versioned).
+    pub fn try_emit_many_versioned<'a>(
+        data: &'a [MtBurn<'a>],
+        version: &'static str,
+    ) -> Result<(), EventError> {
+        Self::to_near_event(data, version).map(NearEvent::emit)
+    }
+
+    /// Validates `data` and wraps it into a [`NearEvent`] under `version` without logging
+    /// it, e.g. to forward its JSON through another channel.
+    pub fn to_near_event<'a>(
+        data: &'a [MtBurn<'a>],
+        version: &'static str,
+    ) -> Result<NearEvent<'a>, EventError> {
+        data.iter()
+            .try_for_each(|d| validate(d.token_ids, None, d.memo))?;
+        Ok(new_246(version, Nep246EventKind::MtBurn(data)))
+    }
+
+    /// Serializes this event data with camelCase keys, for off-chain consumers such as
+    /// API responses. Keys are emitted in alphabetical order. Events logged on chain
+    /// always use snake_case.
+    pub fn to_camel_case_json(&self) -> String {
+        to_camel_case_json(self)
+    }
+}
+
+/// Data to log for an MT approve event. To log this event,
+/// call [`.emit()`](MtApprove::emit).
+///
+/// This event is an extension beyond the three events of NEP-246, logged under the same
+/// `standard` and `version` so indexers can track which accounts may transfer whose tokens.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtApprove<'a> {
+    pub owner_id: &'a AccountId,
+    pub approved_id: &'a AccountId,
+    pub token_ids: &'a [&'a str],
+    /// Amount approved for each entry of `token_ids`, as decimal `U128` strings.
+    /// Must have the same length as `token_ids`.
+    pub amounts: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl MtApprove<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    /// Emits an mt approve event, through [`env::log_str`](near_sdk::env::log_str),
+    /// where each [`MtApprove`] represents the data of each approval.
+    ///
+    /// Panics if [`try_emit_many`](MtApprove::try_emit_many) would return an error.
+    pub fn emit_many(data: &[MtApprove<'_>]) {
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    pub fn try_emit_many(data: &[MtApprove<'_>]) -> Result<(), EventError> {
+        Self::to_near_event(data, MT_EVENT_VERSION).map(NearEvent::emit)
+    }
+
+    /// Validates `data` and wraps it into a [`NearEvent`] under `version` without logging
+    /// it, e.g. to forward its JSON through another channel.
+    pub fn to_near_event<'a>(
+        data: &'a [MtApprove<'a>],
+        version: &'static str,
+    ) -> Result<NearEvent<'a>, EventError> {
+        data.iter()
+            .try_for_each(|d| validate(d.token_ids, Some(d.amounts), d.memo))?;
+        Ok(new_246(version, Nep246EventKind::MtApprove(data)))
+    }
+}
+
+/// Data to log for an MT register event. To log this event,
+/// call [`.emit()`](MtRegister::emit).
+///
+/// Like [`MtApprove`], this event is an extension beyond the three events of NEP-246.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtRegister<'a> {
+    pub account_id: &'a AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl MtRegister<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    /// Emits an mt register event, through [`env::log_str`](near_sdk::env::log_str),
+    /// where each [`MtRegister`] represents one account.
+    ///
+    /// Panics if [`try_emit_many`](MtRegister::try_emit_many) would return an error.
+    pub fn emit_many(data: &[MtRegister<'_>]) {
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    pub fn try_emit_many(data: &[MtRegister<'_>]) -> Result<(), EventError> {
+        data.iter().try_for_each(|d| validate_memo(d.memo))?;
+        new_246(MT_EVENT_VERSION, Nep246EventKind::MtRegister(data)).emit();
+        Ok(())
+    }
+}
+
+/// Data to log for an MT unregister event. To log this event,
+/// call [`.emit()`](MtUnregister::emit).
+///
+/// Like [`MtApprove`], this event is an extension beyond the three events of NEP-246.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtUnregister<'a> {
+    pub account_id: &'a AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl MtUnregister<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    /// Emits an mt unregister event, through [`env::log_str`](near_sdk::env::log_str),
+    /// where each [`MtUnregister`] represents one account.
+    ///
+    /// Panics if [`try_emit_many`](MtUnregister::try_emit_many) would return an error.
+    pub fn emit_many(data: &[MtUnregister<'_>]) {
+        expect_valid(Self::try_emit_many(data))
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        Self::try_emit_many(&[self])
+    }
+
+    /// Validates every entry of `data` and logs them as one event. Nothing is logged
+    /// if any entry is malformed.
+    pub fn try_emit_many(data: &[MtUnregister<'_>]) -> Result<(), EventError> {
+        data.iter().try_for_each(|d| validate_memo(d.memo))?;
+        new_246(MT_EVENT_VERSION, Nep246EventKind::MtUnregister(data)).emit();
+        Ok(())
+    }
+}
+
+/// Data to log when the contract is paused. To log this event,
+/// call [`.emit()`](MtPause::emit).
+///
+/// Like [`MtApprove`], this event is an extension beyond the three events of NEP-246.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtPause<'a> {
+    /// The admin who paused the contract.
+    pub account_id: &'a AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl MtPause<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        expect_valid(self.try_emit())
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        validate_memo(self.memo)?;
+        new_246(MT_EVENT_VERSION, Nep246EventKind::MtPause(&[self])).emit();
+        Ok(())
+    }
+}
+
+/// Data to log when the contract is unpaused. To log this event,
+/// call [`.emit()`](MtUnpause::emit).
+///
+/// Like [`MtApprove`], this event is an extension beyond the three events of NEP-246.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct MtUnpause<'a> {
+    /// The admin who unpaused the contract.
+    pub account_id: &'a AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl MtUnpause<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        expect_valid(self.try_emit())
+    }
+
+    /// Validates the event and logs it, or returns why it is malformed.
+    pub fn try_emit(self) -> Result<(), EventError> {
+        validate_memo(self.memo)?;
+        new_246(MT_EVENT_VERSION, Nep246EventKind::MtUnpause(&[self])).emit();
+        Ok(())
+    }
+}
+
+impl<'a> MtMint<'a> {
+    /// Starts an [`MtMint`] with no authorized id or memo.
+    pub fn builder(owner_id: &'a AccountId, token_ids: &'a [&'a str]) -> MtMintBuilder<'a> {
+        MtMintBuilder(MtMint {
+            owner_id,
+            token_ids,
+            authorized_id: None,
+            memo: None,
+        })
     }
 }
 
+/// Builder for [`MtMint`], created with [`MtMint::builder`].
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct MtMintBuilder<'a>(MtMint<'a>);
+
+impl<'a> MtMintBuilder<'a> {
+    pub fn authorized_id(mut self, authorized_id: &'a AccountId) -> Self {
+        self.0.authorized_id = Some(authorized_id);
+        self
+    }
+
+    pub fn memo(mut self, memo: &'a str) -> Self {
+        self.0.memo = Some(memo);
+        self
+    }
+
+    pub fn build(self) -> MtMint<'a> {
+        self.0
+    }
+
+    pub fn emit(self) {
+        self.0.emit()
+    }
+}
+
+impl<'a> MtTransfer<'a> {
+    /// Starts an [`MtTransfer`] with no amounts, authorized id or memo. Amounts must be set
+    /// with [`amounts`](MtTransferBuilder::amounts) before emitting.
+    pub fn builder(
+        old_owner_id: &'a AccountId,
+        new_owner_id: &'a AccountId,
+        token_ids: &'a [&'a str],
+    ) -> MtTransferBuilder<'a> {
+        MtTransferBuilder(MtTransfer {
+            old_owner_id,
+            new_owner_id,
+            token_ids,
+            amounts: &[],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        })
+    }
+}
+
+/// Builder for [`MtTransfer`], created with [`MtTransfer::builder`].
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct MtTransferBuilder<'a>(MtTransfer<'a>);
+
+impl<'a> MtTransferBuilder<'a> {
+    pub fn amounts(mut self, amounts: &'a [&'a str]) -> Self {
+        self.0.amounts = amounts;
+        self
+    }
+
+    pub fn authorized_id(mut self, authorized_id: &'a AccountId) -> Self {
+        self.0.authorized_id = Some(authorized_id);
+        self
+    }
+
+    pub fn approval_ids(mut self, approval_ids: &'a [u64]) -> Self {
+        self.0.approval_ids = Some(approval_ids);
+        self
+    }
+
+    pub fn memo(mut self, memo: &'a str) -> Self {
+        self.0.memo = Some(memo);
+        self
+    }
+
+    pub fn memos(mut self, memos: &'a [Option<&'a str>]) -> Self {
+        self.0.memos = Some(memos);
+        self
+    }
+
+    pub fn build(self) -> MtTransfer<'a> {
+        self.0
+    }
+
+    pub fn emit(self) {
+        self.0.emit()
+    }
+}
+
+impl<'a> MtBurn<'a> {
+    /// Starts an [`MtBurn`] with no authorized id or memo.
+    pub fn builder(owner_id: &'a AccountId, token_ids: &'a [&'a str]) -> MtBurnBuilder<'a> {
+        MtBurnBuilder(MtBurn {
+            owner_id,
+            token_ids,
+            authorized_id: None,
+            memo: None,
+        })
+    }
+}
+
+/// Builder for [`MtBurn`], created with [`MtBurn::builder`].
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct MtBurnBuilder<'a>(MtBurn<'a>);
+
+impl<'a> MtBurnBuilder<'a> {
+    pub fn authorized_id(mut self, authorized_id: &'a AccountId) -> Self {
+        self.0.authorized_id = Some(authorized_id);
+        self
+    }
+
+    pub fn memo(mut self, memo: &'a str) -> Self {
+        self.0.memo = Some(memo);
+        self
+    }
+
+    pub fn build(self) -> MtBurn<'a> {
+        self.0
+    }
+
+    pub fn emit(self) {
+        self.0.emit()
+    }
+}
+
+fn to_camel_case_json<T: Serialize>(data: &T) -> String {
+    fn camel_case(key: &str) -> String {
+        let mut parts = key.split('_');
+        let mut out = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                out.extend(first.to_uppercase());
+                out.push_str(chars.as_str());
+            }
+        }
+        out
+    }
+
+    fn rename_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => map
+                .into_iter()
+                .map(|(k, v)| (camel_case(&k), rename_keys(v)))
+                .collect(),
+            serde_json::Value::Array(values) => values.into_iter().map(rename_keys).collect(),
+            other => other,
+        }
+    }
+
+    // Event data cannot fail to serialize so fine to panic on error
+    #[allow(clippy::redundant_closure)]
+    serde_json::to_value(data)
+        .map(rename_keys)
+        .ok()
+        .unwrap_or_else(|| env::abort())
+        .to_string()
+}
+
+/// Reason an event was rejected by `try_emit`/`try_emit_many`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    /// An entry has no `token_ids`.
+    EmptyTokenIds,
+    /// An entry's `token_ids` and `amounts` have different lengths.
+    LengthMismatch { token_ids: usize, amounts: usize },
+    /// A memo contains control characters, which could break line-oriented log parsing.
+    InvalidMemo,
+    /// A transfer's `token_ids` and `approval_ids` have different lengths.
+    ApprovalIdsLengthMismatch {
+        token_ids: usize,
+        approval_ids: usize,
+    },
+    /// A transfer sets both `memo` and `memos`.
+    MemoAndMemos,
+    /// A transfer's `token_ids` and `memos` have different lengths.
+    MemosLengthMismatch { token_ids: usize, memos: usize },
+}
+
+impl std::fmt::Display for EventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventError::EmptyTokenIds => write!(f, "token_ids must not be empty"),
+            EventError::LengthMismatch { token_ids, amounts } => write!(
+                f,
+                "token_ids and amounts must have the same length, got {} and {}",
+                token_ids, amounts
+            ),
+            EventError::InvalidMemo => write!(f, "Memo must not contain control characters"),
+            EventError::ApprovalIdsLengthMismatch {
+                token_ids,
+                approval_ids,
+            } => write!(
+                f,
+                "token_ids and approval_ids must have the same length, got {} and {}",
+                token_ids, approval_ids
+            ),
+            EventError::MemoAndMemos => write!(f, "memo and memos are mutually exclusive"),
+            EventError::MemosLengthMismatch { token_ids, memos } => write!(
+                f,
+                "token_ids and memos must have the same length, got {} and {}",
+                token_ids, memos
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+/// Borrows every id of every entry as `&str`, one `Vec` per entry.
+fn as_strs<'a, S: AsRef<str> + 'a>(token_ids: impl Iterator<Item = &'a [S]>) -> Vec<Vec<&'a str>> {
+    token_ids
+        .map(|ids| ids.iter().map(AsRef::as_ref).collect())
+        .collect()
+}
+
+fn expect_valid(result: Result<(), EventError>) {
+    if let Err(e) = result {
+        require!(false, e.to_string());
+    }
+}
+
+fn validate(
+    token_ids: &[&str],
+    amounts: Option<&[&str]>,
+    memo: Option<&str>,
+) -> Result<(), EventError> {
+    if token_ids.is_empty() {
+        return Err(EventError::EmptyTokenIds);
+    }
+    if let Some(amounts) = amounts {
+        if token_ids.len() != amounts.len() {
+            return Err(EventError::LengthMismatch {
+                token_ids: token_ids.len(),
+                amounts: amounts.len(),
+            });
+        }
+    }
+    validate_memo(memo)
+}
+
+fn validate_memo(memo: Option<&str>) -> Result<(), EventError> {
+    // Memos are user supplied, so reject anything that could break line-oriented log parsing.
+    if memo.is_some_and(|memo| memo.chars().any(char::is_control)) {
+        return Err(EventError::InvalidMemo);
+    }
+    Ok(())
+}
+
+/// A NEP-246 event with its `version`, as wrapped by [`NearEvent::Nep246`]. Relayers can
+/// inspect an event through its accessors, or build one with [`Nep246Event::new`] to
+/// re-emit it.
 #[derive(Serialize, Debug)]
-pub(crate) struct Nep246Event<'a> {
+pub struct Nep246Event<'a> {
     version: &'static str,
     #[serde(flatten)]
     event_kind: Nep246EventKind<'a>,
 }
 
-#[derive(Serialize, Debug)]
+/// The `event` and `data` of a [`Nep246Event`].
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum Nep246EventKind<'a> {
+    MtMint(&'a [MtMint<'a>]),
+    MtTransfer(&'a [MtTransfer<'a>]),
+    MtBurn(&'a [MtBurn<'a>]),
+    MtApprove(&'a [MtApprove<'a>]),
+    MtRegister(&'a [MtRegister<'a>]),
+    MtUnregister(&'a [MtUnregister<'a>]),
+    MtPause(&'a [MtPause<'a>]),
+    MtUnpause(&'a [MtUnpause<'a>]),
+}
+
+impl<'a> Nep246Event<'a> {
+    /// Wraps `event_kind` under `version`. Unlike the `emit` methods of the event structs,
+    /// this does not validate the event data.
+    pub fn new(version: &'static str, event_kind: Nep246EventKind<'a>) -> Self {
+        Self {
+            version,
+            event_kind,
+        }
+    }
+
+    /// The events schema version written into the `version` field.
+    pub fn version(&self) -> &'static str {
+        self.version
+    }
+
+    pub fn event_kind(&self) -> &Nep246EventKind<'a> {
+        &self.event_kind
+    }
+
+    /// Same as [`Nep246EventKind::event_name`].
+    pub fn event_name(&self) -> &'static str {
+        self.event_kind.event_name()
+    }
+}
+
+impl<'a> Nep246EventKind<'a> {
+    /// The value of the event's `event` field, such as `"mt_transfer"`.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Nep246EventKind::MtMint(_) => "mt_mint",
+            Nep246EventKind::MtTransfer(_) => "mt_transfer",
+            Nep246EventKind::MtBurn(_) => "mt_burn",
+            Nep246EventKind::MtApprove(_) => "mt_approve",
+            Nep246EventKind::MtRegister(_) => "mt_register",
+            Nep246EventKind::MtUnregister(_) => "mt_unregister",
+            Nep246EventKind::MtPause(_) => "mt_pause",
+            Nep246EventKind::MtUnpause(_) => "mt_unpause",
+        }
+    }
+
+    /// Every token id of every entry, in order. Empty for account events, which carry
+    /// no token ids.
+    pub fn token_ids(&self) -> Vec<&'a str> {
+        fn flatten<'a, T>(data: &'a [T], token_ids: impl Fn(&T) -> &'a [&'a str]) -> Vec<&'a str> {
+            data.iter().flat_map(token_ids).copied().collect()
+        }
+        match self {
+            Nep246EventKind::MtMint(data) => flatten(data, |d| d.token_ids),
+            Nep246EventKind::MtTransfer(data) => flatten(data, |d| d.token_ids),
+            Nep246EventKind::MtBurn(data) => flatten(data, |d| d.token_ids),
+            Nep246EventKind::MtApprove(data) => flatten(data, |d| d.token_ids),
+            Nep246EventKind::MtRegister(_)
+            | Nep246EventKind::MtUnregister(_)
+            | Nep246EventKind::MtPause(_)
+            | Nep246EventKind::MtUnpause(_) => Vec::new(),
+        }
+    }
+}
+
+pub(crate) fn new_246<'a>(version: &'static str, event_kind: Nep246EventKind<'a>) -> NearEvent<'a> {
+    NearEvent::Nep246(Nep246Event::new(version, event_kind))
+}
+
+/// Prefix NEP-297 puts in front of every event log line.
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// Owned counterpart of [`MtMint`], for deserializing logged events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MtMintOwned {
+    pub owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<AccountId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+impl From<&MtMint<'_>> for MtMintOwned {
+    fn from(mint: &MtMint<'_>) -> Self {
+        Self {
+            owner_id: mint.owner_id.clone(),
+            token_ids: mint.token_ids.iter().map(|id| id.to_string()).collect(),
+            authorized_id: mint.authorized_id.cloned(),
+            memo: mint.memo.map(str::to_string),
+        }
+    }
+}
+
+/// Owned counterpart of [`MtTransfer`], for deserializing logged events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MtTransferOwned {
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    pub amounts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<AccountId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_ids: Option<Vec<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memos: Option<Vec<Option<String>>>,
+}
+
+impl From<&MtTransfer<'_>> for MtTransferOwned {
+    fn from(transfer: &MtTransfer<'_>) -> Self {
+        Self {
+            old_owner_id: transfer.old_owner_id.clone(),
+            new_owner_id: transfer.new_owner_id.clone(),
+            token_ids: transfer.token_ids.iter().map(|id| id.to_string()).collect(),
+            amounts: transfer.amounts.iter().map(|a| a.to_string()).collect(),
+            authorized_id: transfer.authorized_id.cloned(),
+            approval_ids: transfer.approval_ids.map(<[u64]>::to_vec),
+            memo: transfer.memo.map(str::to_string),
+            memos: transfer
+                .memos
+                .map(|memos| memos.iter().map(|m| m.map(str::to_string)).collect()),
+        }
+    }
+}
+
+/// Owned counterpart of [`MtBurn`], for deserializing logged events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MtBurnOwned {
+    pub owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<AccountId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+impl From<&MtBurn<'_>> for MtBurnOwned {
+    fn from(burn: &MtBurn<'_>) -> Self {
+        Self {
+            owner_id: burn.owner_id.clone(),
+            token_ids: burn.token_ids.iter().map(|id| id.to_string()).collect(),
+            authorized_id: burn.authorized_id.cloned(),
+            memo: burn.memo.map(str::to_string),
+        }
+    }
+}
+
+/// Owned counterpart of [`MtApprove`], for deserializing logged events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MtApproveOwned {
+    pub owner_id: AccountId,
+    pub approved_id: AccountId,
+    pub token_ids: Vec<String>,
+    pub amounts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+impl From<&MtApprove<'_>> for MtApproveOwned {
+    fn from(approve: &MtApprove<'_>) -> Self {
+        Self {
+            owner_id: approve.owner_id.clone(),
+            approved_id: approve.approved_id.clone(),
+            token_ids: approve.token_ids.iter().map(|id| id.to_string()).collect(),
+            amounts: approve.amounts.iter().map(|a| a.to_string()).collect(),
+            approval_id: approve.approval_id,
+            memo: approve.memo.map(str::to_string),
+        }
+    }
+}
+
+/// Owned counterpart of [`MtRegister`], [`MtUnregister`], [`MtPause`] and [`MtUnpause`],
+/// for deserializing logged events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MtAccountOwned {
+    pub account_id: AccountId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// A parsed NEP-246 event, as returned by [`parse_event`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
 #[allow(clippy::enum_variant_names)]
-enum Nep246EventKind<'a> {
-    MtMint(&'a [MtMint<'a>]),
-    MtTransfer(&'a [MtTransfer<'a>]),
-    MtBurn(&'a [MtBurn<'a>]),
+pub enum Nep246EventKindOwned {
+    MtMint(Vec<MtMintOwned>),
+    MtTransfer(Vec<MtTransferOwned>),
+    MtBurn(Vec<MtBurnOwned>),
+    MtApprove(Vec<MtApproveOwned>),
+    MtRegister(Vec<MtAccountOwned>),
+    MtUnregister(Vec<MtAccountOwned>),
+    MtPause(Vec<MtAccountOwned>),
+    MtUnpause(Vec<MtAccountOwned>),
 }
 
-fn new_246<'a>(version: &'static str, event_kind: Nep246EventKind<'a>) -> NearEvent<'a> {
-    NearEvent::Nep246(Nep246Event {
-        version,
-        event_kind,
-    })
+#[derive(Deserialize)]
+struct Nep246EventEnvelope {
+    standard: String,
+    #[serde(flatten)]
+    event_kind: Nep246EventKindOwned,
 }
 
-fn new_246_v1(event_kind: Nep246EventKind) -> NearEvent {
-    new_246("1.0.0", event_kind)
+/// Reason a log line could not be parsed by [`parse_event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The line does not start with `EVENT_JSON:`.
+    MissingPrefix,
+    /// The payload is not a well-formed NEP-246 event.
+    InvalidJson(String),
+    /// The event belongs to another standard.
+    UnknownStandard(String),
+    /// An amount is not a base-10 integer, e.g. a human-readable `"1.5"`.
+    InvalidAmount(String),
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::{test_utils, AccountId};
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::MissingPrefix => {
+                write!(f, "Log line is not an {} event", EVENT_JSON_PREFIX)
+            }
+            ParseError::InvalidJson(e) => write!(f, "Invalid event JSON: {}", e),
+            ParseError::UnknownStandard(standard) => {
+                write!(f, "Event standard {} is not nep246", standard)
+            }
+            ParseError::InvalidAmount(amount) => {
+                write!(f, "Amount {:?} is not an integer", amount)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
 
-    fn bob() -> AccountId {
-        AccountId::new_unchecked("bob".to_string())
+/// Parses an `EVENT_JSON:` log line written by this module back into owned event data.
+///
+/// Transfer and approval amounts must be plain base-10 integers, the way a `U128` is
+/// logged, so decimal strings a client let slip into an amount field are rejected.
+pub fn parse_event(log_line: &str) -> Result<Nep246EventKindOwned, ParseError> {
+    let json = log_line
+        .strip_prefix(EVENT_JSON_PREFIX)
+        .ok_or(ParseError::MissingPrefix)?;
+    let envelope: Nep246EventEnvelope =
+        serde_json::from_str(json).map_err(|e| ParseError::InvalidJson(e.to_string()))?;
+    if envelope.standard != "nep246" {
+        return Err(ParseError::UnknownStandard(envelope.standard));
     }
+    match &envelope.event_kind {
+        Nep246EventKindOwned::MtTransfer(transfers) => transfers
+            .iter()
+            .try_for_each(|transfer| check_amounts(&transfer.amounts))?,
+        Nep246EventKindOwned::MtApprove(approvals) => approvals
+            .iter()
+            .try_for_each(|approval| check_amounts(&approval.amounts))?,
+        _ => {}
+    }
+    Ok(envelope.event_kind)
+}
 
-    fn alice() -> AccountId {
-        AccountId::new_unchecked("alice".to_string())
+fn check_amounts(amounts: &[String]) -> Result<(), ParseError> {
+    match amounts
+        .iter()
+        .find(|amount| amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()))
+    {
+        Some(amount) => Err(ParseError::InvalidAmount(amount.clone())),
+        None => Ok(()),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{account, assert_event};
+    use near_sdk::test_utils;
+    use serde_json::json;
 
     #[test]
     fn mt_mint() {
-        let owner_id = &bob();
+        let owner_id = &account("bob");
         let token_ids = &["0", "1"];
         MtMint {
             owner_id,
             token_ids,
+            authorized_id: None,
             memo: None,
         }
         .emit();
@@ -148,34 +1279,64 @@ mod tests {
             test_utils::get_logs()[0],
             r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["0","1"]}]}"#
         );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_mint",
+            "data": [
+                {
+                    "owner_id": "bob",
+                    "token_ids": ["0", "1"]
+                }
+            ]
+        }));
     }
 
     #[test]
     fn mt_mints() {
-        let owner_id = &bob();
+        let owner_id = &account("bob");
         let token_ids = &["0", "1"];
         let mint_log = MtMint {
             owner_id,
             token_ids,
+            authorized_id: None,
             memo: None,
         };
         MtMint::emit_many(&[
             mint_log,
             MtMint {
-                owner_id: &alice(),
+                owner_id: &account("alice"),
                 token_ids: &["2", "3"],
+                authorized_id: Some(&account("bob")),
                 memo: Some("has memo"),
             },
         ]);
         assert_eq!(
             test_utils::get_logs()[0],
-            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["0","1"]},{"owner_id":"alice","token_ids":["2","3"],"memo":"has memo"}]}"#
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["0","1"]},{"owner_id":"alice","token_ids":["2","3"],"authorized_id":"bob","memo":"has memo"}]}"#
         );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_mint",
+            "data": [
+                {
+                    "owner_id": "bob",
+                    "token_ids": ["0", "1"]
+                },
+                {
+                    "owner_id": "alice",
+                    "token_ids": ["2", "3"],
+                    "authorized_id": "bob",
+                    "memo": "has memo"
+                }
+            ]
+        }));
     }
 
     #[test]
     fn mt_burn() {
-        let owner_id = &bob();
+        let owner_id = &account("bob");
         let token_ids = &["0", "1"];
         MtBurn {
             owner_id,
@@ -188,17 +1349,28 @@ mod tests {
             test_utils::get_logs()[0],
             r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"bob","token_ids":["0","1"]}]}"#
         );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_burn",
+            "data": [
+                {
+                    "owner_id": "bob",
+                    "token_ids": ["0", "1"]
+                }
+            ]
+        }));
     }
 
     #[test]
     fn mt_burns() {
-        let owner_id = &bob();
+        let owner_id = &account("bob");
         let token_ids = &["0", "1"];
         MtBurn::emit_many(&[
             MtBurn {
-                owner_id: &alice(),
+                owner_id: &account("alice"),
                 token_ids: &["2", "3"],
-                authorized_id: Some(&bob()),
+                authorized_id: Some(&account("bob")),
                 memo: Some("has memo"),
             },
             MtBurn {
@@ -212,51 +1384,786 @@ mod tests {
             test_utils::get_logs()[0],
             r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["2","3"],"authorized_id":"bob","memo":"has memo"},{"owner_id":"bob","token_ids":["0","1"]}]}"#
         );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_burn",
+            "data": [
+                {
+                    "owner_id": "alice",
+                    "token_ids": ["2", "3"],
+                    "authorized_id": "bob",
+                    "memo": "has memo"
+                },
+                {
+                    "owner_id": "bob",
+                    "token_ids": ["0", "1"]
+                }
+            ]
+        }));
     }
 
     #[test]
     fn mt_transfer() {
-        let old_owner_id = &bob();
-        let new_owner_id = &alice();
+        let old_owner_id = &account("bob");
+        let new_owner_id = &account("alice");
         let token_ids = &["0", "1"];
         MtTransfer {
             old_owner_id,
             new_owner_id,
             token_ids,
+            amounts: &["1", "100"],
             authorized_id: None,
+            approval_ids: None,
             memo: None,
+            memos: None,
         }
         .emit();
         assert_eq!(
             test_utils::get_logs()[0],
-            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"]}]}"#
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"],"amounts":["1","100"]}]}"#
         );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_transfer",
+            "data": [
+                {
+                    "old_owner_id": "bob",
+                    "new_owner_id": "alice",
+                    "token_ids": ["0", "1"],
+                    "amounts": ["1", "100"]
+                }
+            ]
+        }));
     }
 
     #[test]
     fn mt_transfers() {
-        let old_owner_id = &bob();
-        let new_owner_id = &alice();
+        let old_owner_id = &account("bob");
+        let new_owner_id = &account("alice");
         let token_ids = &["0", "1"];
         MtTransfer::emit_many(&[
             MtTransfer {
-                old_owner_id: &alice(),
-                new_owner_id: &bob(),
+                old_owner_id: &account("alice"),
+                new_owner_id: &account("bob"),
                 token_ids: &["2", "3"],
-                authorized_id: Some(&bob()),
+                amounts: &["1", "100"],
+                authorized_id: Some(&account("bob")),
+                approval_ids: None,
                 memo: Some("has memo"),
+                memos: None,
             },
             MtTransfer {
                 old_owner_id,
                 new_owner_id,
                 token_ids,
+                amounts: &["1", "100"],
                 authorized_id: None,
+                approval_ids: None,
+                memo: None,
+                memos: None,
+            },
+        ]);
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["2","3"],"amounts":["1","100"],"authorized_id":"bob","memo":"has memo"},{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"],"amounts":["1","100"]}]}"#
+        );
+        assert_event(json!({
+            "standard": "nep246",
+            "version": "1.0.0",
+            "event": "mt_transfer",
+            "data": [
+                {
+                    "old_owner_id": "alice",
+                    "new_owner_id": "bob",
+                    "token_ids": ["2", "3"],
+                    "amounts": ["1", "100"],
+                    "authorized_id": "bob",
+                    "memo": "has memo"
+                },
+                {
+                    "old_owner_id": "bob",
+                    "new_owner_id": "alice",
+                    "token_ids": ["0", "1"],
+                    "amounts": ["1", "100"]
+                }
+            ]
+        }));
+    }
+
+    #[test]
+    fn emit_from_owned_ids() {
+        let owner_id = account("bob");
+        let token_ids = vec!["gold".to_string(), "sword".to_string()];
+        MtMint::emit_many_from(&[MtMint {
+            owner_id: &owner_id,
+            token_ids: &token_ids,
+            authorized_id: None,
+            memo: Some("drop"),
+        }]);
+        MtTransfer::emit_many_from(&[MtTransfer {
+            old_owner_id: &owner_id,
+            new_owner_id: &account("alice"),
+            token_ids: &token_ids,
+            amounts: &["1", "2"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        }]);
+        MtBurn::emit_many_from(&[MtBurn {
+            owner_id: &owner_id,
+            token_ids: &token_ids,
+            authorized_id: None,
+            memo: None,
+        }]);
+        assert_eq!(
+            test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["gold","sword"],"memo":"drop"}]}"#,
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["gold","sword"],"amounts":["1","2"]}]}"#,
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"bob","token_ids":["gold","sword"]}]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn mt_mint_emit_single_matches_emit() {
+        let owner_id = account("bob");
+        let mut buffer = String::new();
+        for token_id in ["gold", "silver"] {
+            MtMint::builder(&owner_id, &[token_id]).memo("drop").emit();
+            MtMint::builder(&owner_id, &[token_id])
+                .memo("drop")
+                .build()
+                .emit_single(&mut buffer);
+        }
+        let logs = test_utils::get_logs();
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0], logs[1]);
+        assert_eq!(logs[2], logs[3]);
+        assert_eq!(buffer, logs[3]);
+    }
+
+    #[test]
+    fn mt_mint_emit_chunked() {
+        let ids: Vec<String> = (0..250).map(|i| format!("token-{}", i)).collect();
+        let token_ids: Vec<Vec<&str>> = ids.iter().map(|id| vec![id.as_str()]).collect();
+        let owner_id = account("bob");
+        let mints: Vec<MtMint> = token_ids
+            .iter()
+            .map(|token_ids| MtMint::builder(&owner_id, token_ids).build())
+            .collect();
+        MtMint::emit_chunked(&mints, 100);
+
+        let logs = test_utils::get_logs();
+        assert_eq!(logs.len(), 3);
+        let mut logged_ids = Vec::new();
+        for line in &logs {
+            match parse_event(line).unwrap() {
+                Nep246EventKindOwned::MtMint(mints) => {
+                    logged_ids.extend(mints.into_iter().flat_map(|mint| mint.token_ids))
+                }
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(logged_ids, ids);
+    }
+
+    #[test]
+    fn mt_mint_emit_chunked_splits_entries() {
+        let owner_id = account("bob");
+        MtMint::emit_chunked(
+            &[
+                MtMint::builder(&owner_id, &["a", "b", "c"]).build(),
+                MtMint::builder(&owner_id, &["d"]).build(),
+            ],
+            2,
+        );
+        assert_eq!(
+            test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["a","b"]}]}"#,
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["c"]},{"owner_id":"bob","token_ids":["d"]}]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn mt_transfer_huge_batch_is_split() {
+        let ids: Vec<String> = (0..500).map(|i| format!("token-{}", i)).collect();
+        let token_ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let amounts = vec!["1"; ids.len()];
+        let memos = vec![Some("m"); ids.len()];
+        let (bob, alice) = (account("bob"), account("alice"));
+        MtTransfer::builder(&bob, &alice, &token_ids)
+            .amounts(&amounts)
+            .memos(&memos)
+            .emit();
+
+        let logs = test_utils::get_logs();
+        assert!(logs.len() > 1);
+        let mut logged_ids = Vec::new();
+        for line in &logs {
+            assert!(line.len() <= MAX_EVENT_LOG_LEN);
+            match parse_event(line).unwrap() {
+                Nep246EventKindOwned::MtTransfer(transfers) => {
+                    for transfer in transfers {
+                        assert_eq!(transfer.token_ids.len(), transfer.amounts.len());
+                        assert_eq!(transfer.memos.unwrap().len(), transfer.token_ids.len());
+                        logged_ids.extend(transfer.token_ids);
+                    }
+                }
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(logged_ids, ids);
+    }
+
+    #[test]
+    fn mt_transfer_with_hook() {
+        MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        }
+        .emit_with_hook(Some("relayer-1"));
+        let logs = test_utils::get_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(
+            logs[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0"],"amounts":["1"]}]}"#
+        );
+        assert_eq!(
+            logs[1],
+            r#"MT_HOOK:{"hook_tag":"relayer-1","event":"mt_transfer"}"#
+        );
+    }
+
+    #[test]
+    fn mt_transfer_without_hook() {
+        MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        }
+        .emit_with_hook(None);
+        let logs = test_utils::get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Memo must not contain control characters")]
+    fn mt_transfer_memo_with_newline() {
+        MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: Some("line\nEVENT_JSON:{}"),
+            memos: None,
+        }
+        .emit();
+    }
+
+    #[test]
+    #[should_panic(expected = "Memo must not contain control characters")]
+    fn mt_mint_memo_with_control_character() {
+        MtMint {
+            owner_id: &account("bob"),
+            token_ids: &["0"],
+            authorized_id: None,
+            memo: Some("tab\there"),
+        }
+        .emit();
+    }
+
+    #[test]
+    fn mt_transfer_camel_case() {
+        let transfer = MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0"],
+            amounts: &["1"],
+            authorized_id: Some(&account("bob")),
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&transfer).unwrap(),
+            r#"{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0"],"amounts":["1"],"authorized_id":"bob"}"#
+        );
+        assert_eq!(
+            transfer.to_camel_case_json(),
+            r#"{"amounts":["1"],"authorizedId":"bob","newOwnerId":"alice","oldOwnerId":"bob","tokenIds":["0"]}"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "token_ids and amounts must have the same length")]
+    fn mt_transfer_amounts_length_mismatch() {
+        MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0", "1"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        }
+        .emit();
+    }
+
+    #[test]
+    fn mt_transfer_per_token_memos() {
+        let (bob, alice) = (account("bob"), account("alice"));
+        MtTransfer::builder(&bob, &alice, &["0", "1", "2"])
+            .amounts(&["1", "2", "3"])
+            .memos(&[Some("first"), None, Some("third")])
+            .emit();
+        let log = &test_utils::get_logs()[0];
+        assert_eq!(
+            log,
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1","2"],"amounts":["1","2","3"],"memos":["first",null,"third"]}]}"#
+        );
+        match parse_event(log).unwrap() {
+            Nep246EventKindOwned::MtTransfer(transfers) => assert_eq!(
+                transfers[0].memos,
+                Some(vec![
+                    Some("first".to_string()),
+                    None,
+                    Some("third".to_string())
+                ])
+            ),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mt_transfer_memos_errors() {
+        let (bob, alice) = (account("bob"), account("alice"));
+        let transfer = MtTransfer::builder(&bob, &alice, &["0", "1"])
+            .amounts(&["1", "1"])
+            .memos(&[Some("a"), Some("b")]);
+        assert_eq!(
+            transfer.clone().memo("both").build().try_emit(),
+            Err(EventError::MemoAndMemos)
+        );
+        assert_eq!(
+            transfer.clone().memos(&[None]).build().try_emit(),
+            Err(EventError::MemosLengthMismatch {
+                token_ids: 2,
+                memos: 1
+            })
+        );
+        assert_eq!(
+            transfer.memos(&[None, Some("a\nb")]).build().try_emit(),
+            Err(EventError::InvalidMemo)
+        );
+        assert!(test_utils::get_logs().is_empty());
+    }
+
+    #[test]
+    fn mt_approve() {
+        let (bob, alice) = (account("bob"), account("alice"));
+        MtApprove::emit_many(&[
+            MtApprove {
+                owner_id: &bob,
+                approved_id: &alice,
+                token_ids: &["0", "1"],
+                amounts: &["1", "100"],
+                approval_id: Some(3),
+                memo: None,
+            },
+            MtApprove {
+                owner_id: &bob,
+                approved_id: &alice,
+                token_ids: &["2"],
+                amounts: &["5"],
+                approval_id: None,
+                memo: Some("listing"),
+            },
+        ]);
+        let log = &test_utils::get_logs()[0];
+        assert_eq!(
+            log,
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_approve","data":[{"owner_id":"bob","approved_id":"alice","token_ids":["0","1"],"amounts":["1","100"],"approval_id":3},{"owner_id":"bob","approved_id":"alice","token_ids":["2"],"amounts":["5"],"memo":"listing"}]}"#
+        );
+        match parse_event(log).unwrap() {
+            Nep246EventKindOwned::MtApprove(approvals) => {
+                assert_eq!(approvals[0].approval_id, Some(3));
+                assert_eq!(approvals[1].memo.as_deref(), Some("listing"));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "token_ids and amounts must have the same length")]
+    fn mt_approve_amounts_length_mismatch() {
+        MtApprove {
+            owner_id: &account("bob"),
+            approved_id: &account("alice"),
+            token_ids: &["0", "1"],
+            amounts: &["1"],
+            approval_id: None,
+            memo: None,
+        }
+        .emit();
+    }
+
+    #[test]
+    fn mt_register_and_unregister() {
+        MtRegister::emit_many(&[
+            MtRegister {
+                account_id: &account("bob"),
                 memo: None,
             },
+            MtRegister {
+                account_id: &account("alice"),
+                memo: Some("airdrop"),
+            },
         ]);
+        MtUnregister {
+            account_id: &account("bob"),
+            memo: None,
+        }
+        .emit();
+        let logs = test_utils::get_logs();
+        assert_eq!(
+            logs[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_register","data":[{"account_id":"bob"},{"account_id":"alice","memo":"airdrop"}]}"#
+        );
+        assert_eq!(
+            parse_event(&logs[1]),
+            Ok(Nep246EventKindOwned::MtUnregister(vec![MtAccountOwned {
+                account_id: account("bob"),
+                memo: None
+            }]))
+        );
+    }
+
+    #[test]
+    fn try_emit_errors() {
+        let transfer = MtTransfer {
+            old_owner_id: &account("bob"),
+            new_owner_id: &account("alice"),
+            token_ids: &["0", "1"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        };
+        assert_eq!(
+            transfer.clone().try_emit(),
+            Err(EventError::LengthMismatch {
+                token_ids: 2,
+                amounts: 1
+            })
+        );
+        assert_eq!(
+            MtTransfer {
+                token_ids: &[],
+                amounts: &[],
+                ..transfer.clone()
+            }
+            .try_emit(),
+            Err(EventError::EmptyTokenIds)
+        );
+        assert_eq!(
+            MtTransfer {
+                amounts: &["1", "1"],
+                approval_ids: Some(&[0]),
+                ..transfer.clone()
+            }
+            .try_emit(),
+            Err(EventError::ApprovalIdsLengthMismatch {
+                token_ids: 2,
+                approval_ids: 1
+            })
+        );
+        assert_eq!(
+            MtBurn {
+                owner_id: &account("bob"),
+                token_ids: &[],
+                authorized_id: None,
+                memo: None,
+            }
+            .try_emit(),
+            Err(EventError::EmptyTokenIds)
+        );
+        assert_eq!(
+            MtMint {
+                owner_id: &account("bob"),
+                token_ids: &["0"],
+                authorized_id: None,
+                memo: Some("a\nb"),
+            }
+            .try_emit(),
+            Err(EventError::InvalidMemo)
+        );
+        assert!(test_utils::get_logs().is_empty());
+    }
+
+    #[test]
+    fn try_emit_many_logs_valid_events() {
+        assert_eq!(
+            MtMint::try_emit_many(&[MtMint {
+                owner_id: &account("bob"),
+                token_ids: &["0"],
+                authorized_id: None,
+                memo: None,
+            }]),
+            Ok(())
+        );
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint","data":[{"owner_id":"bob","token_ids":["0"]}]}"#
+        );
+    }
+
+    #[test]
+    fn builders_match_struct_literals() {
+        let (bob, alice) = (account("bob"), account("alice"));
+        let token_ids = &["0", "1"];
+
+        let mint = MtMint {
+            owner_id: &bob,
+            token_ids,
+            authorized_id: None,
+            memo: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&MtMint::builder(&bob, token_ids).build()).unwrap(),
+            serde_json::to_string(&mint).unwrap()
+        );
+
+        let transfer = MtTransfer {
+            old_owner_id: &bob,
+            new_owner_id: &alice,
+            token_ids,
+            amounts: &["1", "100"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        };
+        let built = MtTransfer::builder(&bob, &alice, token_ids)
+            .amounts(&["1", "100"])
+            .build();
+        assert_eq!(
+            serde_json::to_string(&built).unwrap(),
+            serde_json::to_string(&transfer).unwrap()
+        );
+
+        let burn = MtBurn {
+            owner_id: &bob,
+            token_ids,
+            authorized_id: Some(&alice),
+            memo: Some("has memo"),
+        };
+        let built = MtBurn::builder(&bob, token_ids)
+            .authorized_id(&alice)
+            .memo("has memo")
+            .build();
+        assert_eq!(
+            serde_json::to_string(&built).unwrap(),
+            serde_json::to_string(&burn).unwrap()
+        );
+    }
+
+    #[test]
+    fn builder_emit() {
+        MtTransfer::builder(&account("bob"), &account("alice"), &["0"])
+            .amounts(&["1"])
+            .memo("has memo")
+            .emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0"],"amounts":["1"],"memo":"has memo"}]}"#
+        );
+    }
+
+    #[test]
+    fn emit_many_versioned() {
+        MtBurn::emit_many_versioned(&[MtBurn::builder(&account("bob"), &["0"]).build()], "1.1.0");
         assert_eq!(
             test_utils::get_logs()[0],
-            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["2","3"],"authorized_id":"bob","memo":"has memo"},{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"]}]}"#
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.1.0","event":"mt_burn","data":[{"owner_id":"bob","token_ids":["0"]}]}"#
+        );
+        MtBurn::builder(&account("bob"), &["0"]).emit();
+        assert!(test_utils::get_logs()[1].contains(&format!(r#""version":"{}""#, MT_EVENT_VERSION)));
+    }
+
+    #[test]
+    fn parse_logged_events() {
+        MtTransfer::emit_many(&[
+            MtTransfer {
+                old_owner_id: &account("alice"),
+                new_owner_id: &account("bob"),
+                token_ids: &["2", "3"],
+                amounts: &["1", "100"],
+                authorized_id: Some(&account("bob")),
+                approval_ids: None,
+                memo: Some("has memo"),
+                memos: None,
+            },
+            MtTransfer::builder(&account("bob"), &account("alice"), &["0"])
+                .amounts(&["5"])
+                .build(),
+        ]);
+        let log = &test_utils::get_logs()[0];
+        assert_eq!(
+            log,
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["2","3"],"amounts":["1","100"],"authorized_id":"bob","memo":"has memo"},{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0"],"amounts":["5"]}]}"#
         );
+        let parsed = parse_event(log).unwrap();
+        assert_eq!(
+            parsed,
+            Nep246EventKindOwned::MtTransfer(vec![
+                MtTransferOwned {
+                    old_owner_id: account("alice"),
+                    new_owner_id: account("bob"),
+                    token_ids: vec!["2".to_string(), "3".to_string()],
+                    amounts: vec!["1".to_string(), "100".to_string()],
+                    authorized_id: Some(account("bob")),
+                    approval_ids: None,
+                    memo: Some("has memo".to_string()),
+                    memos: None,
+                },
+                (&MtTransfer::builder(&account("bob"), &account("alice"), &["0"])
+                    .amounts(&["5"])
+                    .build())
+                    .into(),
+            ])
+        );
+
+        let owner_id = account("bob");
+        let operator_id = account("alice");
+        let mint = MtMint::builder(&owner_id, &["0", "1"])
+            .authorized_id(&operator_id)
+            .build();
+        let owned = MtMintOwned::from(&mint);
+        mint.emit();
+        assert_eq!(
+            parse_event(&test_utils::get_logs()[1]),
+            Ok(Nep246EventKindOwned::MtMint(vec![owned]))
+        );
+    }
+
+    #[test]
+    fn parse_event_errors() {
+        assert_eq!(
+            parse_event(r#"{"standard":"nep246"}"#),
+            Err(ParseError::MissingPrefix)
+        );
+        assert!(matches!(
+            parse_event(r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_mint"}"#),
+            Err(ParseError::InvalidJson(_))
+        ));
+        assert_eq!(
+            parse_event(
+                r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"mt_mint","data":[]}"#
+            ),
+            Err(ParseError::UnknownStandard("nep171".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_event_rejects_decimal_amounts() {
+        assert_eq!(
+            parse_event(
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"],"amounts":["1","1.5"]}]}"#
+            ),
+            Err(ParseError::InvalidAmount("1.5".to_string()))
+        );
+        assert_eq!(
+            parse_event(
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_approve","data":[{"owner_id":"bob","approved_id":"alice","token_ids":["0"],"amounts":[""]}]}"#
+            ),
+            Err(ParseError::InvalidAmount("".to_string()))
+        );
+        assert_eq!(
+            ParseError::InvalidAmount("1.5".to_string()).to_string(),
+            r#"Amount "1.5" is not an integer"#
+        );
+        assert!(parse_event(
+            r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0"],"amounts":["15"]}]}"#
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn near_event_json_matches_log() {
+        let owner_id = account("bob");
+        let data = [MtMint::builder(&owner_id, &["0"]).memo("has memo").build()];
+        let event = MtMint::to_near_event(&data, MT_EVENT_VERSION).unwrap();
+        let line = event.to_event_json_string();
+        assert_eq!(line, format!("EVENT_JSON:{}", event.to_json_string()));
+        event.emit();
+        assert_eq!(test_utils::get_logs()[0], line);
+        assert_eq!(
+            MtMint::to_near_event(
+                &[MtMint::builder(&account("bob"), &[]).build()],
+                MT_EVENT_VERSION
+            )
+            .unwrap_err(),
+            EventError::EmptyTokenIds
+        );
+    }
+
+    #[test]
+    fn event_kind_is_introspectable() {
+        let (bob, alice) = (account("bob"), account("alice"));
+        let data = [
+            MtTransfer::builder(&bob, &alice, &["0", "1"])
+                .amounts(&["1", "2"])
+                .build(),
+            MtTransfer::builder(&alice, &bob, &["2"])
+                .amounts(&["3"])
+                .build(),
+        ];
+        let kind = Nep246EventKind::MtTransfer(&data);
+        assert_eq!(kind.event_name(), "mt_transfer");
+        assert_eq!(kind.token_ids(), ["0", "1", "2"]);
+        assert!(Nep246EventKind::MtRegister(&[MtRegister {
+            account_id: &bob,
+            memo: None
+        }])
+        .token_ids()
+        .is_empty());
+
+        let event = Nep246Event::new("1.1.0", kind);
+        assert_eq!(event.version(), "1.1.0");
+        assert_eq!(event.event_name(), "mt_transfer");
+        match event.event_kind() {
+            Nep246EventKind::MtTransfer(transfers) => assert_eq!(transfers.len(), 2),
+            other => panic!("unexpected event {:?}", other),
+        }
+        NearEvent::Nep246(Nep246Event::new(
+            MT_EVENT_VERSION,
+            Nep246EventKind::MtTransfer(&data),
+        ))
+        .emit();
+        MtTransfer::emit_many(&data);
+        let logs = test_utils::get_logs();
+        assert_eq!(logs[0], logs[1]);
     }
 }
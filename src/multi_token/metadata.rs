@@ -1,7 +1,8 @@
+use crate::multi_token::token::TokenId;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::Base64VecU8;
-use near_sdk::require;
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, require};
 
 /// This spec can be treated like a version of the standard.
 pub const MT_METADATA_SPEC: &str = "mt-1.0.0";
@@ -10,7 +11,7 @@ pub const MT_METADATA_SPEC: &str = "mt-1.0.0";
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
-pub struct MTContractMetadata {
+pub struct MtContractMetadata {
     pub spec: String,              // required, essentially a version like "mt-1.0.0"
     pub name: String,              // required, ex. "Mosaics"
     pub symbol: String,            // required, ex. "MOSIAC"
@@ -20,6 +21,41 @@ pub struct MTContractMetadata {
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
+/// Metadata shared by every token minted from the same base, such as the units of a
+/// fungible id or a series of collectibles.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct MtBaseTokenMetadata {
+    pub name: String,              // required, ex. "Silver Swords" or "Metaverse 3"
+    pub id: String,                // required, a unique identifier for the metadata
+    pub symbol: Option<String>,    // required only for semi-fungible tokens, ex. "MOCHI"
+    pub icon: Option<String>,      // Data URL
+    pub decimals: Option<u8>,      // number of decimals to render amounts with
+    pub base_uri: Option<String>, // Centralized gateway known to have reliable access to decentralized storage assets referenced by `reference` or `media` URLs
+    pub reference: Option<String>, // URL to a JSON file with more info
+    pub copies: Option<u64>, // number of copies of this set of metadata in existence when token was minted.
+    pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+}
+
+/// Metadata specific to one token, as defined by NEP-245.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct MtTokenMetadata {
+    pub title: Option<String>, // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
+    pub description: Option<String>, // free-form description
+    pub media: Option<String>, // URL to associated media, preferably to decentralized, content-addressed storage
+    pub media_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
+    pub issued_at: Option<String>,       // ISO 8601 datetime when token was issued or minted
+    pub expires_at: Option<String>,      // ISO 8601 datetime when token expires
+    pub starts_at: Option<String>,       // ISO 8601 datetime when token starts being valid
+    pub updated_at: Option<String>,      // ISO 8601 datetime when token was last updated
+    pub extra: Option<String>, // anything extra the MT wants to store on-chain. Can be stringified JSON.
+    pub reference: Option<String>, // URL to an off-chain JSON file with more info.
+    pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+}
+
 /// Metadata on the individual token level.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
 #[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
@@ -37,14 +73,40 @@ pub struct TokenMetadata {
     pub extra: Option<String>, // anything extra the MT wants to store on-chain. Can be stringified JSON.
     pub reference: Option<String>, // URL to an off-chain JSON file with more info.
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+    pub decimals: Option<u8>, // display hint for fungible ids: decimals to render amounts with
+    pub symbol: Option<String>, // display hint for fungible ids, ex. "GOLD"
+}
+
+/// What [`internal_mint_with_metadata`](crate::multi_token::core::MultiToken::internal_mint_with_metadata)
+/// does when minting more of an id that already has metadata.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MetadataOnRemint {
+    /// Panic, so supply expansions can't silently change a token's metadata.
+    #[default]
+    Reject,
+    /// Mint the supply and keep the existing metadata.
+    Ignore,
+    /// Mint the supply and replace the existing metadata.
+    Overwrite,
 }
 
-/// Offers details on the contract-level metadata.
-pub trait NonFungibleTokenMetadataProvider {
-    fn mt_metadata(&self) -> MTContractMetadata;
+/// Offers details on the contract-level, base and token-level metadata. The batch
+/// methods return one entry per requested id, `None` for ids without metadata.
+pub trait MultiTokenMetadataProvider {
+    fn mt_metadata_contract(&self) -> MtContractMetadata;
+
+    fn mt_metadata_base_by_token_id(
+        &self,
+        token_ids: Vec<TokenId>,
+    ) -> Vec<Option<MtBaseTokenMetadata>>;
+
+    fn mt_metadata_token_by_token_id(
+        &self,
+        token_ids: Vec<TokenId>,
+    ) -> Vec<Option<MtTokenMetadata>>;
 }
 
-impl MTContractMetadata {
+impl MtContractMetadata {
     pub fn assert_valid(&self) {
         require!(self.spec == MT_METADATA_SPEC, "Spec is not MT metadata");
         require!(
@@ -57,6 +119,62 @@ impl MTContractMetadata {
     }
 }
 
+impl MtBaseTokenMetadata {
+    pub fn assert_valid(&self) {
+        require!(
+            self.reference.is_some() == self.reference_hash.is_some(),
+            "Reference and reference hash must be present"
+        );
+        if let Some(reference_hash) = &self.reference_hash {
+            require!(reference_hash.0.len() == 32, "Hash has to be 32 bytes");
+        }
+    }
+}
+
+impl MtTokenMetadata {
+    pub fn assert_valid(&self) {
+        require!(
+            self.media.is_some() == self.media_hash.is_some(),
+            "Media and media hash must be present"
+        );
+        if let Some(media_hash) = &self.media_hash {
+            require!(media_hash.0.len() == 32, "Media hash has to be 32 bytes");
+        }
+
+        require!(
+            self.reference.is_some() == self.reference_hash.is_some(),
+            "Reference and reference hash must be present"
+        );
+        if let Some(reference_hash) = &self.reference_hash {
+            require!(
+                reference_hash.0.len() == 32,
+                "Reference hash has to be 32 bytes"
+            );
+        }
+    }
+}
+
+/// Keeps the NEP-245 token-level fields. `copies` is dropped, and the `decimals` and
+/// `symbol` display hints are returned on [`Token`](crate::multi_token::token::Token)
+/// itself.
+impl From<TokenMetadata> for MtTokenMetadata {
+    fn from(metadata: TokenMetadata) -> Self {
+        Self {
+            title: metadata.title,
+            description: metadata.description,
+            media: metadata.media,
+            media_hash: metadata.media_hash,
+            issued_at: metadata.issued_at,
+            expires_at: metadata.expires_at,
+            starts_at: metadata.starts_at,
+            updated_at: metadata.updated_at,
+            extra: metadata.extra,
+            reference: metadata.reference,
+            reference_hash: metadata.reference_hash,
+        }
+    }
+}
+
 impl TokenMetadata {
     pub fn assert_valid(&self) {
         require!(self.media.is_some() == self.media_hash.is_some());
@@ -72,4 +190,143 @@ impl TokenMetadata {
             );
         }
     }
+
+    /// Checks fetched `media` content against `media_hash`. Returns `None` if no hash
+    /// is declared, otherwise whether the SHA-256 of `content` matches it.
+    pub fn verify_media_hash(&self, content: &[u8]) -> Option<bool> {
+        self.media_hash
+            .as_ref()
+            .map(|hash| sha256_matches(hash, content))
+    }
+
+    /// Checks fetched `reference` JSON against `reference_hash`. Returns `None` if no
+    /// hash is declared, otherwise whether the SHA-256 of `content` matches it.
+    pub fn verify_reference_hash(&self, content: &[u8]) -> Option<bool> {
+        self.reference_hash
+            .as_ref()
+            .map(|hash| sha256_matches(hash, content))
+    }
+
+    /// Parses `extra` as JSON. Returns `None` if `extra` is unset or not valid JSON.
+    pub fn extra_value(&self) -> Option<serde_json::Value> {
+        self.extra
+            .as_deref()
+            .and_then(|extra| serde_json::from_str(extra).ok())
+    }
+
+    /// Stores `value` in `extra` as a JSON string.
+    pub fn set_extra_value(&mut self, value: &serde_json::Value) {
+        self.extra = Some(value.to_string());
+    }
+}
+
+fn sha256_matches(hash: &Base64VecU8, content: &[u8]) -> bool {
+    env::sha256(content) == hash.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // sha256("hello")
+    const HELLO_SHA256: [u8; 32] = [
+        0x2c, 0xf2, 0x4d, 0xba, 0x5f, 0xb0, 0xa3, 0x0e, 0x26, 0xe8, 0x3b, 0x2a, 0xc5, 0xb9, 0xe2,
+        0x9e, 0x1b, 0x16, 0x1e, 0x5c, 0x1f, 0xa7, 0x42, 0x5e, 0x73, 0x04, 0x33, 0x62, 0x93, 0x8b,
+        0x98, 0x24,
+    ];
+
+    fn metadata() -> TokenMetadata {
+        TokenMetadata {
+            title: None,
+            description: None,
+            media: Some("ipfs://media".to_string()),
+            media_hash: Some(Base64VecU8(HELLO_SHA256.to_vec())),
+            copies: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+            decimals: None,
+            symbol: None,
+        }
+    }
+
+    fn contract_metadata() -> MtContractMetadata {
+        MtContractMetadata {
+            spec: MT_METADATA_SPEC.to_string(),
+            name: "Mosaics".to_string(),
+            symbol: "MOSAIC".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: Some("ipfs://reference".to_string()),
+            reference_hash: Some(Base64VecU8(HELLO_SHA256.to_vec())),
+        }
+    }
+
+    #[test]
+    fn contract_metadata_is_valid() {
+        contract_metadata().assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Reference and reference hash must be present")]
+    fn contract_metadata_hash_requires_reference() {
+        MtContractMetadata {
+            reference: None,
+            ..contract_metadata()
+        }
+        .assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Media hash has to be 32 bytes")]
+    fn token_metadata_checks_media_hash() {
+        MtTokenMetadata {
+            title: Some("Parcel #5055".to_string()),
+            description: None,
+            media: Some("ipfs://media".to_string()),
+            media_hash: Some(Base64VecU8(vec![0; 31])),
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+        .assert_valid();
+    }
+
+    #[test]
+    fn verify_media_hash() {
+        let metadata = metadata();
+        assert_eq!(metadata.verify_media_hash(b"hello"), Some(true));
+        assert_eq!(metadata.verify_media_hash(b"goodbye"), Some(false));
+    }
+
+    #[test]
+    fn verify_reference_hash() {
+        let mut metadata = metadata();
+        assert_eq!(metadata.verify_reference_hash(b"hello"), None);
+        metadata.reference = Some("ipfs://reference".to_string());
+        metadata.reference_hash = Some(Base64VecU8(HELLO_SHA256.to_vec()));
+        assert_eq!(metadata.verify_reference_hash(b"hello"), Some(true));
+        assert_eq!(metadata.verify_reference_hash(b"hello!"), Some(false));
+    }
+
+    #[test]
+    fn extra_value_round_trip() {
+        let mut metadata = metadata();
+        assert_eq!(metadata.extra_value(), None);
+
+        let value = serde_json::json!({ "rarity": "epic", "level": 3 });
+        metadata.set_extra_value(&value);
+        assert_eq!(metadata.extra_value(), Some(value));
+
+        metadata.extra = Some("not json".to_string());
+        assert_eq!(metadata.extra_value(), None);
+    }
 }
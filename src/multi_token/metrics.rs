@@ -0,0 +1,122 @@
+//! Counters of emitted NEP-246 events, for tests and local profiling of a contract's event
+//! output. Only compiled with the `event-metrics` feature and never part of contract state.
+//!
+//! Counters are kept per thread rather than in shared atomics. A contract runs on a single
+//! wasm thread, one call per instance, so on chain they are simply per call and atomics
+//! would buy nothing. Off chain, `cargo test` runs tests on parallel threads, and shared
+//! counters would mix the events of tests running at the same time. Per thread, a test
+//! only sees what its own thread emitted: events logged from a thread the test spawns are
+//! not counted on the test's thread, and a thread reused for several tests, e.g. with
+//! `--test-threads=1`, keeps counting across them, so tests should call
+//! [`reset_event_counts`] first.
+
+use std::cell::Cell;
+
+/// Number of events logged per kind since the last [`reset_event_counts`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventCounts {
+    pub mt_mint: u64,
+    pub mt_transfer: u64,
+    pub mt_burn: u64,
+    pub mt_approve: u64,
+    pub mt_register: u64,
+    pub mt_unregister: u64,
+    pub mt_pause: u64,
+    pub mt_unpause: u64,
+}
+
+thread_local! {
+    static EVENT_COUNTS: Cell<EventCounts> = Cell::new(EventCounts::default());
+}
+
+/// Returns how many events of each kind were emitted on this thread.
+pub fn event_counts() -> EventCounts {
+    EVENT_COUNTS.with(Cell::get)
+}
+
+/// Sets all counters back to zero.
+pub fn reset_event_counts() {
+    EVENT_COUNTS.with(|counts| counts.set(EventCounts::default()))
+}
+
+pub(crate) fn record(event: &str) {
+    EVENT_COUNTS.with(|counts| {
+        let mut current = counts.get();
+        match event {
+            "mt_mint" => current.mt_mint += 1,
+            "mt_transfer" => current.mt_transfer += 1,
+            "mt_burn" => current.mt_burn += 1,
+            "mt_approve" => current.mt_approve += 1,
+            "mt_register" => current.mt_register += 1,
+            "mt_unregister" => current.mt_unregister += 1,
+            "mt_pause" => current.mt_pause += 1,
+            "mt_unpause" => current.mt_unpause += 1,
+            _ => {}
+        }
+        counts.set(current);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::events::{MtBurn, MtMint, MtTransfer};
+    use near_sdk::AccountId;
+
+    #[test]
+    fn counts_emitted_events() {
+        let bob = AccountId::new_unchecked("bob".to_string());
+        let alice = AccountId::new_unchecked("alice".to_string());
+        reset_event_counts();
+        MtMint {
+            owner_id: &bob,
+            token_ids: &["0"],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        MtMint {
+            owner_id: &alice,
+            token_ids: &["1"],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        MtTransfer {
+            old_owner_id: &bob,
+            new_owner_id: &alice,
+            token_ids: &["0"],
+            amounts: &["1"],
+            authorized_id: None,
+            approval_ids: None,
+            memo: None,
+            memos: None,
+        }
+        .emit();
+        assert_eq!(
+            event_counts(),
+            EventCounts {
+                mt_mint: 2,
+                mt_transfer: 1,
+                mt_burn: 0,
+                mt_approve: 0,
+                mt_register: 0,
+                mt_unregister: 0,
+                mt_pause: 0,
+                mt_unpause: 0,
+            }
+        );
+
+        MtBurn {
+            owner_id: &alice,
+            token_ids: &["0", "1"],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        assert_eq!(event_counts().mt_burn, 1);
+
+        reset_event_counts();
+        assert_eq!(event_counts(), EventCounts::default());
+    }
+}
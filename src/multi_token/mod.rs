@@ -4,12 +4,34 @@ pub mod token;
 
 pub mod approval;
 
+pub mod burn;
+
+pub mod enumeration;
+
+pub mod error;
+
 pub mod metadata;
 
+mod storage_impl;
+pub use storage_impl::StorageAccount;
+
+mod pause;
+
+mod relayer;
+
+mod receiver_allowlist;
+
+mod escrow;
+
+mod snapshot;
+
+#[cfg(feature = "validate")]
+mod validate;
+
 pub mod utils;
 
+#[cfg(feature = "events")]
 pub mod events;
 
-pub mod macros;
-
-pub use macros::*;
+#[cfg(feature = "event-metrics")]
+pub mod metrics;
@@ -0,0 +1,150 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::error::{abort, MtError};
+#[cfg(feature = "events")]
+use crate::multi_token::events::{MtPause, MtUnpause};
+use near_sdk::{assert_one_yocto, env, require};
+
+impl MultiToken {
+    /// Whether transfers and approvals are currently blocked by [`pause`](MultiToken::pause).
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Panics with `"Contract is paused"` while the contract is paused. Every mutating
+    /// `MultiTokenCore` and `MultiTokenApproval` method calls it first; views, the
+    /// `mt_resolve_transfer` callback and the `internal_*` methods are left open, so
+    /// tokens in flight still get refunded and the contract can recover state.
+    pub fn assert_not_paused(&self) {
+        if self.paused {
+            abort(MtError::Paused);
+        }
+    }
+
+    /// Blocks all transfers and approvals and emits an `MtPause` event. Requires one
+    /// yoctoNEAR and can only be called by the admin given to
+    /// [`with_pause`](MultiToken::with_pause).
+    pub fn pause(&mut self) {
+        self.internal_set_paused(true);
+    }
+
+    /// Lifts a [`pause`](MultiToken::pause) and emits an `MtUnpause` event, with the same
+    /// requirements.
+    pub fn unpause(&mut self) {
+        self.internal_set_paused(false);
+    }
+
+    fn internal_set_paused(&mut self, paused: bool) {
+        assert_one_yocto();
+        let admin_id = env::predecessor_account_id();
+        require!(
+            self.pause_admin.as_ref() == Some(&admin_id),
+            "Only the pause admin can pause or unpause"
+        );
+        require!(
+            self.paused != paused,
+            if paused {
+                "Contract is already paused"
+            } else {
+                "Contract is not paused"
+            }
+        );
+        self.paused = paused;
+        #[cfg(feature = "events")]
+        if paused {
+            MtPause {
+                account_id: &admin_id,
+                memo: None,
+            }
+            .emit();
+        } else {
+            MtUnpause {
+                account_id: &admin_id,
+                memo: None,
+            }
+            .emit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::approval::MultiTokenApproval;
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId};
+
+    fn as_account(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(1)
+            .build());
+    }
+
+    /// Alice holds 100 gold, and the contract was just paused by danny, its pause admin.
+    fn setup() -> MultiToken {
+        as_account(accounts(0));
+        let mut mt = MultiToken::new(b"m")
+            .with_approvals()
+            .with_pause(accounts(3));
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        as_account(accounts(3));
+        mt.pause();
+        as_account(accounts(0));
+        mt
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn paused_transfer_fails() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn paused_approve_fails() {
+        let mut mt = setup();
+        mt.mt_approve(vec!["gold".into()], vec![U128(1)], accounts(1), None);
+    }
+
+    #[test]
+    fn views_and_internals_work_while_paused() {
+        let mut mt = setup();
+        assert!(mt.is_paused());
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        mt.internal_transfer(&accounts(0), &accounts(1), &"gold".into(), 10, None, None);
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(10));
+    }
+
+    #[test]
+    fn unpause_allows_transfers_again() {
+        let mut mt = setup();
+        as_account(accounts(3));
+        mt.unpause();
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_unpause","data":[{"account_id":"danny"}]}"#
+            ]
+        );
+        as_account(accounts(0));
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the pause admin can pause or unpause")]
+    fn only_admin_can_unpause() {
+        let mut mt = setup();
+        mt.unpause();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the pause admin can pause or unpause")]
+    fn pause_requires_the_flag() {
+        as_account(accounts(0));
+        MultiToken::new(b"m").pause();
+    }
+}
@@ -0,0 +1,107 @@
+use crate::multi_token::core::MultiToken;
+use near_sdk::collections::LookupSet;
+use near_sdk::{require, AccountId};
+
+fn expect_allowlist(
+    allowed_receivers: Option<&mut LookupSet<AccountId>>,
+) -> &mut LookupSet<AccountId> {
+    if allowed_receivers.is_none() {
+        require!(false, "The receiver allowlist is not enabled");
+    }
+    allowed_receivers.unwrap()
+}
+
+impl MultiToken {
+    /// Whether `mt_transfer_call` may call `receiver_id`: always, unless the receiver
+    /// allowlist is enabled and doesn't list it.
+    pub fn is_allowed_receiver(&self, receiver_id: &AccountId) -> bool {
+        self.allowed_receivers
+            .as_ref()
+            .is_none_or(|allowed_receivers| allowed_receivers.contains(receiver_id))
+    }
+
+    /// Panics with `"Receiver is not allowlisted"` unless
+    /// [`is_allowed_receiver`](MultiToken::is_allowed_receiver).
+    pub fn assert_receiver_allowed(&self, receiver_id: &AccountId) {
+        require!(
+            self.is_allowed_receiver(receiver_id),
+            "Receiver is not allowlisted"
+        );
+    }
+
+    /// Adds `receiver_id` to the receivers `mt_transfer_call` may call. Returns `false` if
+    /// it was already listed. Access control is up to the contract.
+    pub fn internal_allow_receiver(&mut self, receiver_id: &AccountId) -> bool {
+        expect_allowlist(self.allowed_receivers.as_mut()).insert(receiver_id)
+    }
+
+    /// Removes `receiver_id` from the receivers `mt_transfer_call` may call. Returns `false`
+    /// if it was not listed. Access control is up to the contract.
+    pub fn internal_disallow_receiver(&mut self, receiver_id: &AccountId) -> bool {
+        expect_allowlist(self.allowed_receivers.as_mut()).remove(receiver_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds 100 gold, and only bob is an allowed receiver.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .prepaid_gas(near_sdk::Gas(300_000_000_000_000))
+            .build());
+        let mut mt = MultiToken::new(b"m").with_receiver_allowlist();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        assert!(mt.internal_allow_receiver(&accounts(1)));
+        mt
+    }
+
+    #[test]
+    fn allowed_receiver_is_called() {
+        let mut mt = setup();
+        let _ = mt.mt_transfer_call(accounts(1), "gold".into(), U128(10), None, None, "".into());
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "Receiver is not allowlisted")]
+    fn other_receivers_are_rejected_up_front() {
+        let mut mt = setup();
+        let _ = mt.mt_transfer_call(accounts(2), "gold".into(), U128(10), None, None, "".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Receiver is not allowlisted")]
+    fn disallowed_receiver_is_rejected() {
+        let mut mt = setup();
+        assert!(mt.internal_disallow_receiver(&accounts(1)));
+        let _ = mt.mt_batch_transfer_call(
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(10)],
+            None,
+            None,
+            "".into(),
+        );
+    }
+
+    #[test]
+    fn plain_transfers_are_not_affected() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(2), "gold".into(), U128(10), None, None);
+        assert_eq!(mt.mt_balance_of(accounts(2), "gold".into()), U128(10));
+    }
+
+    #[test]
+    fn everyone_is_allowed_without_the_allowlist() {
+        let mut mt = setup();
+        mt.allowed_receivers = None;
+        assert!(mt.is_allowed_receiver(&accounts(2)));
+    }
+}
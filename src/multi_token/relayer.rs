@@ -0,0 +1,144 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::token::TokenId;
+use near_sdk::collections::LookupSet;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, require, AccountId};
+
+fn expect_relayers(
+    trusted_relayers: Option<&mut LookupSet<AccountId>>,
+) -> &mut LookupSet<AccountId> {
+    if trusted_relayers.is_none() {
+        require!(false, "Relayed transfers are not enabled");
+    }
+    trusted_relayers.unwrap()
+}
+
+impl MultiToken {
+    /// Whether `account_id` may call [`mt_relayed_transfer`](MultiToken::mt_relayed_transfer).
+    pub fn is_trusted_relayer(&self, account_id: &AccountId) -> bool {
+        self.trusted_relayers
+            .as_ref()
+            .is_some_and(|trusted_relayers| trusted_relayers.contains(account_id))
+    }
+
+    /// Lets `relayer_id` transfer on behalf of any user. The relayer is trusted to have
+    /// authenticated the user, e.g. by checking a signed meta-transaction, before it
+    /// passes their account as `sender_id`. Access control is up to the contract.
+    pub fn internal_add_trusted_relayer(&mut self, relayer_id: &AccountId) {
+        expect_relayers(self.trusted_relayers.as_mut()).insert(relayer_id);
+    }
+
+    /// Revokes what [`internal_add_trusted_relayer`](MultiToken::internal_add_trusted_relayer)
+    /// granted. Access control is up to the contract.
+    pub fn internal_remove_trusted_relayer(&mut self, relayer_id: &AccountId) {
+        expect_relayers(self.trusted_relayers.as_mut()).remove(relayer_id);
+    }
+
+    /// Like `mt_transfer`, but sent by a trusted relayer for `sender_id`, who the tokens
+    /// are taken from instead of the predecessor. `mt_transfer` itself keeps using the
+    /// predecessor only. Requires one yoctoNEAR and panics unless the predecessor is a
+    /// trusted relayer.
+    pub fn mt_relayed_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_not_paused();
+        require!(
+            self.is_trusted_relayer(&env::predecessor_account_id()),
+            "Relayer is not trusted"
+        );
+        self.internal_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_id,
+            amount.0,
+            approval,
+            memo,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId};
+
+    fn as_account(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(1)
+            .build());
+    }
+
+    /// Alice holds 100 gold, and danny is a trusted relayer.
+    fn setup() -> MultiToken {
+        as_account(accounts(0));
+        let mut mt = MultiToken::new(b"m").with_trusted_relayers();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        mt.internal_add_trusted_relayer(&accounts(3));
+        mt
+    }
+
+    #[test]
+    fn trusted_relayer_transfers_for_the_sender() {
+        let mut mt = setup();
+        as_account(accounts(3));
+        mt.mt_relayed_transfer(
+            accounts(0),
+            accounts(1),
+            "gold".into(),
+            U128(30),
+            None,
+            None,
+        );
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(70));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(30));
+        assert_eq!(mt.mt_balance_of(accounts(3), "gold".into()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Relayer is not trusted")]
+    fn untrusted_relayer_is_rejected() {
+        let mut mt = setup();
+        as_account(accounts(2));
+        mt.mt_relayed_transfer(
+            accounts(0),
+            accounts(1),
+            "gold".into(),
+            U128(30),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Relayer is not trusted")]
+    fn removed_relayer_is_rejected() {
+        let mut mt = setup();
+        mt.internal_remove_trusted_relayer(&accounts(3));
+        as_account(accounts(3));
+        mt.mt_relayed_transfer(
+            accounts(0),
+            accounts(1),
+            "gold".into(),
+            U128(30),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Relayed transfers are not enabled")]
+    fn relayers_require_the_extension() {
+        as_account(accounts(0));
+        MultiToken::new(b"m").internal_add_trusted_relayer(&accounts(3));
+    }
+}
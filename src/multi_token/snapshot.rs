@@ -0,0 +1,137 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::{require, AccountId, Balance};
+
+impl MultiToken {
+    /// Takes a snapshot of every balance and returns its ID, for reading past balances
+    /// through [`mt_balance_of_at`](MultiToken::mt_balance_of_at). Access control is up to
+    /// the contract.
+    ///
+    /// Taking a snapshot is free. Balances are copied on write instead: the first change to
+    /// a balance after a snapshot stores what it was, 24 bytes, paid for by the account like
+    /// its balance. A balance's first checkpoint also takes a storage record, about 50 bytes
+    /// plus the length of the account and token IDs. Every checkpoint of a balance is kept
+    /// and read back on each change, so snapshots suit occasional uses such as governance
+    /// votes, not one per block.
+    pub fn take_snapshot(&mut self) -> u64 {
+        require!(
+            self.balance_checkpoints.is_some(),
+            "Snapshots are not enabled"
+        );
+        self.snapshot_id += 1;
+        self.snapshot_id
+    }
+
+    /// Returns the balance `account_id` had of `token_id` when snapshot `snapshot_id` was
+    /// taken, with `mt_balance_of`'s conventions. Panics for a snapshot not taken yet.
+    pub fn mt_balance_of_at(
+        &self,
+        account_id: AccountId,
+        token_id: TokenId,
+        snapshot_id: u64,
+    ) -> U128 {
+        require!(
+            0 < snapshot_id && snapshot_id <= self.snapshot_id,
+            "Unknown snapshot ID"
+        );
+        let checkpoints = self
+            .balance_checkpoints
+            .as_ref()
+            .and_then(|checkpoints| checkpoints.get(&(account_id.clone(), token_id.clone())))
+            .unwrap_or_default();
+        // The first checkpoint at or after the snapshot holds the balance as it was then,
+        // since nothing changed it in between. Without one, it hasn't changed since.
+        let i = checkpoints.partition_point(|&(id, _)| id < snapshot_id);
+        match checkpoints.get(i) {
+            Some(&(_, balance)) => balance.into(),
+            None => self.internal_balance_of(&account_id, &token_id).into(),
+        }
+    }
+
+    /// Records `balance` as the balance of `account_id` for `token_id` at the latest snapshot,
+    /// unless that was done already. Called before every balance change.
+    pub(crate) fn internal_checkpoint(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        balance: Balance,
+    ) {
+        let snapshot_id = self.snapshot_id;
+        let checkpoints = match self.balance_checkpoints.as_mut() {
+            Some(checkpoints) if snapshot_id > 0 => checkpoints,
+            _ => return,
+        };
+        let key = (account_id.clone(), token_id.clone());
+        let mut account_checkpoints = checkpoints.get(&key).unwrap_or_default();
+        if account_checkpoints
+            .last()
+            .is_none_or(|&(id, _)| id < snapshot_id)
+        {
+            account_checkpoints.push((snapshot_id, balance));
+            checkpoints.insert(&key, &account_checkpoints);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds 100 gold, with snapshots enabled.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_snapshots();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        mt
+    }
+
+    #[test]
+    fn snapshot_keeps_past_balances() {
+        let mut mt = setup();
+        let first = mt.take_snapshot();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(30), None, None);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(10), None, None);
+        let second = mt.take_snapshot();
+        let third = mt.take_snapshot();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(60), None, None);
+
+        let balances_at = |id| [0, 1].map(|i| mt.mt_balance_of_at(accounts(i), "gold".into(), id));
+        assert_eq!(balances_at(first), [U128(100), U128(0)]);
+        assert_eq!(balances_at(second), [U128(60), U128(40)]);
+        assert_eq!(balances_at(third), [U128(60), U128(40)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(0));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(100));
+    }
+
+    #[test]
+    fn unchanged_balance_reads_the_current_one() {
+        let mut mt = setup();
+        let id = mt.take_snapshot();
+        assert_eq!(
+            mt.mt_balance_of_at(accounts(0), "gold".into(), id),
+            U128(100)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown snapshot ID")]
+    fn future_snapshot_is_unknown() {
+        let mt = setup();
+        let _ = mt.mt_balance_of_at(accounts(0), "gold".into(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshots are not enabled")]
+    fn snapshots_require_the_extension() {
+        let mut mt = setup();
+        mt.balance_checkpoints = None;
+        mt.take_snapshot();
+    }
+}
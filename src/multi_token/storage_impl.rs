@@ -0,0 +1,628 @@
+use crate::multi_token::core::MultiToken;
+#[cfg(feature = "events")]
+use crate::multi_token::events::{MtBurn, MtRegister, MtUnregister};
+use crate::multi_token::token::TokenId;
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, log, require, AccountId, Balance, Promise, StorageUsage};
+
+/// Storage record of a registered account.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageAccount {
+    /// NEAR deposited through `storage_deposit`, including the registration minimum.
+    pub deposit: Balance,
+    /// Bytes used by the account's balances, paid for out of `deposit`.
+    pub used_bytes: StorageUsage,
+}
+
+fn expect_storage_management<T>(storage_accounts: Option<T>) -> T {
+    if storage_accounts.is_none() {
+        require!(false, "Storage management is not enabled");
+    }
+    storage_accounts.unwrap()
+}
+
+/// Panics unless `account_id` parses as a NEAR account ID: lower case, without leading,
+/// trailing or doubled separators. `AccountId::new_unchecked` only checks this in debug
+/// builds.
+fn assert_valid_account_id(account_id: &str) {
+    require!(
+        account_id.parse::<AccountId>().is_ok(),
+        "Invalid account ID"
+    );
+}
+
+impl MultiToken {
+    pub(crate) fn measure_account_storage_usage(&mut self) {
+        let storage_accounts = self.storage_accounts.as_mut().unwrap();
+        let initial_storage_usage = env::storage_usage();
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+        storage_accounts.insert(
+            &tmp_account_id,
+            &StorageAccount {
+                deposit: 0,
+                used_bytes: 0,
+            },
+        );
+        self.account_storage_usage = env::storage_usage() - initial_storage_usage;
+        storage_accounts.remove(&tmp_account_id);
+    }
+
+    /// Registers `account_id` with an empty storage deposit, so the contract pays for the
+    /// registration itself. The account still needs a deposit through `storage_deposit`
+    /// before it can hold tokens. Panics if the account is already registered, or if
+    /// `account_id` is no valid NEAR account ID, e.g. one with upper case letters that was
+    /// built with `AccountId::new_unchecked`, since tokens sent there could never move again.
+    pub fn internal_register_account(&mut self, account_id: &AccountId) {
+        assert_valid_account_id(account_id.as_str());
+        let storage_accounts = expect_storage_management(self.storage_accounts.as_mut());
+        require!(
+            !storage_accounts.contains_key(account_id),
+            "The account is already registered"
+        );
+        let account = StorageAccount {
+            deposit: 0,
+            used_bytes: 0,
+        };
+        storage_accounts.insert(account_id, &account);
+    }
+
+    /// Removes the registration of `account_id` and returns it, leaving any balances and
+    /// the refund of its deposit to the caller. Panics if the account is not registered.
+    pub fn internal_unregister_account(&mut self, account_id: &AccountId) -> StorageAccount {
+        let storage_accounts = expect_storage_management(self.storage_accounts.as_mut());
+        let account = storage_accounts.remove(account_id);
+        if account.is_none() {
+            require!(false, "The account is not registered");
+        }
+        account.unwrap()
+    }
+
+    /// Whether `account_id` may hold tokens: it is registered, or storage management is off.
+    pub(crate) fn internal_can_hold_tokens(&self, account_id: &AccountId) -> bool {
+        self.storage_accounts
+            .as_ref()
+            .is_none_or(|storage_accounts| storage_accounts.contains_key(account_id))
+    }
+
+    /// Charges `account_id` for the storage used since `initial_storage_usage`, or credits it
+    /// for the storage freed. Panics if the account is not registered or its deposit can't
+    /// cover the new usage. Does nothing without storage management.
+    pub(crate) fn internal_track_storage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        self.internal_update_storage(account_id, initial_storage_usage, true);
+    }
+
+    /// Like [`internal_track_storage`](MultiToken::internal_track_storage), but charges the
+    /// account even past its deposit, for storage it can't turn down, such as a refund. Its
+    /// available storage balance then stays at zero until it deposits more.
+    pub(crate) fn internal_charge_storage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        self.internal_update_storage(account_id, initial_storage_usage, false);
+    }
+
+    fn internal_update_storage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+        check_deposit: bool,
+    ) {
+        let min_balance = self.storage_balance_bounds().min.0;
+        let storage_accounts = match self.storage_accounts.as_mut() {
+            Some(storage_accounts) => storage_accounts,
+            None => return,
+        };
+        match storage_accounts.get(account_id) {
+            Some(mut account) => {
+                let storage_usage = env::storage_usage();
+                if storage_usage == initial_storage_usage {
+                    return;
+                }
+                account.used_bytes = if storage_usage > initial_storage_usage {
+                    account.used_bytes + (storage_usage - initial_storage_usage)
+                } else {
+                    account
+                        .used_bytes
+                        .saturating_sub(initial_storage_usage - storage_usage)
+                };
+                require!(
+                    !check_deposit
+                        || storage_usage < initial_storage_usage
+                        || min_balance + storage_cost(account.used_bytes) <= account.deposit,
+                    format!(
+                        "The account {} doesn't have enough storage balance",
+                        account_id
+                    )
+                );
+                storage_accounts.insert(account_id, &account);
+            }
+            None => require!(
+                false,
+                format!("The account {} is not registered", account_id)
+            ),
+        }
+    }
+
+    /// Internal method that returns the Account ID and its burned balances in case the
+    /// account was unregistered.
+    pub fn internal_storage_unregister(
+        &mut self,
+        force: Option<bool>,
+    ) -> Option<(AccountId, Vec<(TokenId, Balance)>)> {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let force = force.unwrap_or(false);
+        if !expect_storage_management(self.storage_accounts.as_ref()).contains_key(&account_id) {
+            log!("The account {} is not registered", &account_id);
+            return None;
+        }
+        let tokens_per_owner = self.tokens_per_owner.as_mut().unwrap();
+        let token_ids = tokens_per_owner
+            .get(&account_id)
+            .map_or_else(Vec::new, |tokens| tokens.to_vec());
+        require!(
+            token_ids.is_empty() || force,
+            "Can't unregister the account with the positive balance without force"
+        );
+        let burned: Vec<(TokenId, Balance)> = token_ids
+            .into_iter()
+            .map(|token_id| {
+                let balance = self.internal_balance_of(&account_id, &token_id);
+                self.internal_withdraw(&account_id, &token_id, balance);
+                (token_id, balance)
+            })
+            .collect();
+        self.tokens_per_owner.as_mut().unwrap().remove(&account_id);
+        let account = self.internal_unregister_account(&account_id);
+        #[cfg(feature = "events")]
+        if !burned.is_empty() {
+            let token_ids: Vec<&str> = burned.iter().map(|(id, _)| id.as_str()).collect();
+            MtBurn::builder(&account_id, &token_ids)
+                .memo("storage_unregister")
+                .build()
+                .emit();
+        }
+        #[cfg(feature = "events")]
+        MtUnregister {
+            account_id: &account_id,
+            memo: None,
+        }
+        .emit();
+        Promise::new(account_id.clone()).transfer(account.deposit);
+        Some((account_id, burned))
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        let min_balance = self.storage_balance_bounds().min.0;
+        self.storage_accounts
+            .as_ref()
+            .and_then(|storage_accounts| storage_accounts.get(account_id))
+            .map(|account| StorageBalance {
+                total: account.deposit.into(),
+                available: account
+                    .deposit
+                    .saturating_sub(min_balance + storage_cost(account.used_bytes))
+                    .into(),
+            })
+    }
+}
+
+fn storage_cost(bytes: StorageUsage) -> Balance {
+    Balance::from(bytes) * env::storage_byte_cost()
+}
+
+impl StorageManagement for MultiToken {
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount: Balance = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let min_balance = self.storage_balance_bounds().min.0;
+        let registered =
+            expect_storage_management(self.storage_accounts.as_ref()).contains_key(&account_id);
+        let (deposit, refund) = if registered && registration_only {
+            log!("The account is already registered, refunding the deposit");
+            (0, amount)
+        } else if registered {
+            (amount, 0)
+        } else {
+            require!(
+                amount >= min_balance,
+                "The attached deposit is less than the minimum storage balance"
+            );
+            self.internal_register_account(&account_id);
+            #[cfg(feature = "events")]
+            MtRegister {
+                account_id: &account_id,
+                memo: None,
+            }
+            .emit();
+            if registration_only {
+                (min_balance, amount - min_balance)
+            } else {
+                (amount, 0)
+            }
+        };
+        if deposit > 0 {
+            let storage_accounts = self.storage_accounts.as_mut().unwrap();
+            let mut account = storage_accounts.get(&account_id).unwrap();
+            account.deposit += deposit;
+            storage_accounts.insert(&account_id, &account);
+        }
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let predecessor_account_id = env::predecessor_account_id();
+        match self.internal_storage_balance_of(&predecessor_account_id) {
+            Some(storage_balance) => {
+                let amount = amount.map_or(storage_balance.available.0, |amount| amount.0);
+                require!(
+                    amount <= storage_balance.available.0,
+                    "The amount is greater than the available storage balance"
+                );
+                if amount > 0 {
+                    let storage_accounts = self.storage_accounts.as_mut().unwrap();
+                    let mut account = storage_accounts.get(&predecessor_account_id).unwrap();
+                    account.deposit -= amount;
+                    storage_accounts.insert(&predecessor_account_id, &account);
+                    Promise::new(predecessor_account_id.clone()).transfer(amount);
+                }
+            }
+            None => require!(
+                false,
+                format!("The account {} is not registered", &predecessor_account_id)
+            ),
+        }
+        self.internal_storage_balance_of(&predecessor_account_id)
+            .unwrap()
+    }
+
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.internal_storage_unregister(force).is_some()
+    }
+
+    /// The minimum covers the registration itself. There is no maximum, as every token an
+    /// account holds uses more storage.
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: storage_cost(self.account_storage_usage).into(),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(&account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::approval::MultiTokenApproval;
+    use crate::multi_token::core::{MultiTokenCore, MultiTokenResolver};
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, PromiseResult, RuntimeFeesConfig, VMConfig, ONE_NEAR};
+
+    fn context(predecessor_account_id: AccountId, attached_deposit: Balance) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(predecessor_account_id)
+            .attached_deposit(attached_deposit)
+            .build());
+    }
+
+    /// Resolves a transfer-call whose `mt_on_transfer` failed, so everything is refunded.
+    fn resolve_failed(mt: &mut MultiToken, amount: Balance) -> Vec<U128> {
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(accounts(5))
+                .predecessor_account_id(accounts(5))
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        mt.mt_resolve_transfer(
+            accounts(0),
+            accounts(1),
+            vec!["gold".into()],
+            vec![U128(amount)],
+            None,
+        )
+    }
+
+    /// Alice is registered with 1 NEAR and holds 100 gold.
+    fn setup() -> MultiToken {
+        context(accounts(0), ONE_NEAR);
+        let mut mt = MultiToken::new(b"m")
+            .with_enumeration()
+            .with_approvals()
+            .with_storage_management();
+        mt.storage_deposit(None, None);
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        context(accounts(0), 1);
+        mt
+    }
+
+    #[test]
+    fn deposit_pays_for_balances() {
+        let mut mt = setup();
+        let min = mt.storage_balance_bounds().min.0;
+        let balance = mt.storage_balance_of(accounts(0)).unwrap();
+        assert_eq!(balance.total, U128(ONE_NEAR));
+        assert!(balance.available.0 < ONE_NEAR - min);
+
+        context(accounts(1), min);
+        let balance = mt.storage_deposit(None, Some(true));
+        assert_eq!((balance.total, balance.available), (U128(min), U128(0)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_register","data":[{"account_id":"bob"}]}"#
+            ]
+        );
+        mt.storage_deposit(None, Some(true));
+        #[cfg(feature = "events")]
+        assert_eq!(near_sdk::test_utils::get_logs().len(), 2);
+        assert!(mt.storage_balance_of(accounts(2)).is_none());
+    }
+
+    #[test]
+    fn registration_accepts_named_and_implicit_accounts() {
+        let mut mt = setup();
+        for account_id in ["market.near", "a_b-c.testnet", &"0f".repeat(32)] {
+            mt.internal_register_account(&account_id.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn registration_rejects_malformed_account_ids() {
+        for account_id in ["Alice.near", "bob..near", ".near", &"0F".repeat(32)] {
+            let result = std::panic::catch_unwind(|| assert_valid_account_id(account_id));
+            assert_eq!(
+                result.unwrap_err().downcast_ref::<String>().unwrap(),
+                "Invalid account ID"
+            );
+        }
+    }
+
+    #[test]
+    fn registered_account_starts_empty() {
+        let mut mt = setup();
+        mt.internal_register_account(&accounts(1));
+        let balance = mt.storage_balance_of(accounts(1)).unwrap();
+        assert_eq!((balance.total, balance.available), (U128(0), U128(0)));
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(0));
+
+        assert_eq!(
+            mt.internal_unregister_account(&accounts(1)),
+            StorageAccount {
+                deposit: 0,
+                used_bytes: 0
+            }
+        );
+        assert!(mt.storage_balance_of(accounts(1)).is_none());
+    }
+
+    #[test]
+    fn double_registration_fails_and_keeps_the_account() {
+        let mut mt = setup();
+        let before = mt.storage_balance_of(accounts(0)).unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mt.internal_register_account(&accounts(0))
+        }));
+        assert_eq!(
+            result.unwrap_err().downcast_ref::<String>().unwrap(),
+            "The account is already registered"
+        );
+        let after = mt.storage_balance_of(accounts(0)).unwrap();
+        assert_eq!(
+            (after.total, after.available),
+            (before.total, before.available)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account is not registered")]
+    fn unregistering_unknown_account_fails() {
+        let mut mt = setup();
+        mt.internal_unregister_account(&accounts(1));
+    }
+
+    #[test]
+    fn unregistered_account_reads_zero_balances() {
+        let mt = setup();
+        assert!(mt.storage_balance_of(accounts(1)).is_none());
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(1), vec!["gold".into(), "gem".into()]),
+            [U128(0), U128(0)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Receiver not registered")]
+    fn unregistered_account_cannot_receive() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+    }
+
+    #[test]
+    fn batch_to_unregistered_receiver_moves_nothing() {
+        let mut mt = setup();
+        mt.internal_mint(&accounts(0), &["gem".into()], &[3], None);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mt.mt_batch_transfer(
+                accounts(1),
+                vec!["gold".into(), "gem".into()],
+                vec![U128(10), U128(1)],
+                None,
+                None,
+            )
+        }));
+        let message = result.unwrap_err();
+        assert_eq!(
+            message.downcast_ref::<String>().map(String::as_str),
+            Some("Receiver not registered")
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(0), vec!["gold".into(), "gem".into()]),
+            [U128(100), U128(3)]
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(accounts(1), vec!["gold".into(), "gem".into()]),
+            [U128(0), U128(0)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob doesn't have enough storage balance")]
+    fn receiver_must_cover_new_balance() {
+        let mut mt = setup();
+        context(accounts(1), mt.storage_balance_bounds().min.0);
+        mt.storage_deposit(None, None);
+        context(accounts(0), 1);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+    }
+
+    #[test]
+    fn emptied_balance_frees_storage() {
+        let mut mt = setup();
+        let available = mt.storage_balance_of(accounts(0)).unwrap().available;
+        context(accounts(1), ONE_NEAR);
+        mt.storage_deposit(None, None);
+        context(accounts(0), 1);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(100), None, None);
+        assert!(mt.storage_balance_of(accounts(0)).unwrap().available.0 > available.0);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(0));
+    }
+
+    #[test]
+    fn burned_balance_frees_storage() {
+        let mut mt = setup();
+        let available = mt.storage_balance_of(accounts(0)).unwrap().available;
+        mt.internal_burn(&accounts(0), &["gold".into()], &[100], None);
+        assert!(mt.storage_balance_of(accounts(0)).unwrap().available.0 > available.0);
+        assert!(mt.balances.get(&(accounts(0), "gold".into())).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The amount is greater than the available storage balance")]
+    fn withdraw_above_available_fails() {
+        let mut mt = setup();
+        let available = mt.storage_balance_of(accounts(0)).unwrap().available;
+        let balance = mt.storage_withdraw(Some(U128(1)));
+        assert_eq!(balance.available.0, available.0 - 1);
+        mt.storage_withdraw(Some(available));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Can't unregister the account with the positive balance without force"
+    )]
+    fn unregister_requires_force_with_balances() {
+        let mut mt = setup();
+        mt.storage_unregister(None);
+    }
+
+    #[test]
+    fn forced_unregister_burns_balances() {
+        let mut mt = setup();
+        assert!(mt.storage_unregister(Some(true)));
+        assert!(mt.storage_balance_of(accounts(0)).is_none());
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(0));
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(0)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"alice","token_ids":["gold"],"memo":"storage_unregister"}]}"#,
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_unregister","data":[{"account_id":"alice"}]}"#,
+            ]
+        );
+        assert!(!mt.storage_unregister(None));
+    }
+
+    #[test]
+    fn forced_unregister_clears_approvals() {
+        let mut mt = setup();
+        mt.mt_approve(vec!["gold".into()], vec![U128(5)], accounts(1), None);
+        assert!(mt.storage_unregister(Some(true)));
+        assert!(mt
+            .approvals_by_id
+            .as_ref()
+            .unwrap()
+            .get(&(accounts(0), "gold".into()))
+            .is_none());
+    }
+
+    #[test]
+    fn approvals_use_owner_storage() {
+        let mut mt = setup();
+        let available = mt.storage_balance_of(accounts(0)).unwrap().available;
+        mt.mt_approve(vec!["gold".into()], vec![U128(5)], accounts(1), None);
+        assert!(mt.storage_balance_of(accounts(0)).unwrap().available.0 < available.0);
+        mt.mt_revoke_all(vec!["gold".into()]);
+        assert_eq!(
+            mt.storage_balance_of(accounts(0)).unwrap().available,
+            available
+        );
+    }
+
+    #[test]
+    fn refund_to_unregistered_owner_is_burned() {
+        let mut mt = setup();
+        context(accounts(1), ONE_NEAR);
+        mt.storage_deposit(None, None);
+        context(accounts(0), 1);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(60), None, None);
+        // Alice leaves while the transfer-call is in flight.
+        assert!(mt.storage_unregister(Some(true)));
+
+        assert_eq!(resolve_failed(&mut mt, 60), [U128(0)]);
+        assert_eq!(mt.mt_balance_of(accounts(1), "gold".into()), U128(0));
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(0)));
+        #[cfg(feature = "events")]
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"nep246","version":"1.0.0","event":"mt_burn","data":[{"owner_id":"bob","token_ids":["gold"],"memo":"refund_unregistered"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn refund_is_charged_past_storage_deposit() {
+        let mut mt = setup();
+        context(accounts(1), ONE_NEAR);
+        mt.storage_deposit(None, None);
+        context(accounts(0), 1);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(100), None, None);
+        // Alice withdraws the storage that paid for her gold balance.
+        mt.storage_withdraw(None);
+
+        assert_eq!(resolve_failed(&mut mt, 100), [U128(0)]);
+        assert_eq!(mt.mt_balance_of(accounts(0), "gold".into()), U128(100));
+        assert_eq!(
+            mt.storage_balance_of(accounts(0)).unwrap().available,
+            U128(0)
+        );
+    }
+}
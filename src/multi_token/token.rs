@@ -1,18 +1,226 @@
-use crate::multi_token::metadata::TokenMetadata;
+use crate::multi_token::metadata::MtTokenMetadata;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 /// Note that token IDs for MTs are strings on NEAR. It's still fine to use autoincrementing numbers as unique IDs if desired, but they should be stringified. This is to make IDs more future-proof as chain-agnostic conventions and standards arise, and allows for more flexibility with considerations like bridging MTs across chains, etc.
-pub type TokenId = String;
+///
+/// A newtype rather than a bare `String`, so other strings can't be passed as token ids by
+/// mistake. It is stored and serialized exactly like the `String` it wraps, so JSON
+/// arguments and existing state stay compatible.
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde", transparent)]
+pub struct TokenId(String);
+
+impl TokenId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for TokenId {
+    fn from(token_id: String) -> Self {
+        Self(token_id)
+    }
+}
+
+impl From<&str> for TokenId {
+    fn from(token_id: &str) -> Self {
+        Self(token_id.to_string())
+    }
+}
+
+impl From<TokenId> for String {
+    fn from(token_id: TokenId) -> Self {
+        token_id.0
+    }
+}
+
+impl AsRef<str> for TokenId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for TokenId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for TokenId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl std::fmt::Display for TokenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Whether a token id is meant to be fungible or to stay a unique, NFT-style token with a
+/// supply of at most 1. Recorded on first mint when the contract enables the registry.
+#[derive(
+    BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub enum TokenKind {
+    Fungible,
+    NonFungible,
+}
 
 /// In this implementation, the Token struct takes two extensions standards (metadata and approval) as optional fields, as they are frequently used in modern MTs.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// Equality and ordering only consider `token_id`, so tokens can be sorted or collected into a
+/// `BTreeSet` by id; two `Token`s with the same id but different owners or metadata compare equal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Token {
     pub token_id: TokenId,
-    pub owner_id: AccountId,
-    pub metadata: Option<TokenMetadata>,
-    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+    /// The only account holding the token, or `None` when its supply is spread across several
+    /// accounts (or fully burned).
+    pub owner_id: Option<AccountId>,
+    /// Total supply of the token across all holders.
+    pub supply: U128,
+    pub metadata: Option<MtTokenMetadata>,
+    /// Number of decimals a wallet should render amounts with, copied from `metadata`.
+    /// Display-only: amounts are always whole units on chain.
+    pub decimals: Option<u8>,
+    /// Short ticker to render next to amounts, copied from `metadata`. Display-only.
+    pub symbol: Option<String>,
+    /// Accounts `owner_id` approved to transfer the token, with their approval IDs. `None`
+    /// without approval management or when the token has no single owner.
+    pub approved_account_ids: Option<BTreeMap<AccountId, u64>>,
+    /// Block timestamp, in nanoseconds, of the token's last mint or transfer. `None` unless
+    /// the contract tracks transfer timestamps.
+    pub last_transfer_at: Option<u64>,
+}
+
+impl Token {
+    /// A token without metadata, display hints or approvals.
+    pub fn new(token_id: TokenId, owner_id: Option<AccountId>, supply: U128) -> Self {
+        Self {
+            token_id,
+            owner_id,
+            supply,
+            metadata: None,
+            decimals: None,
+            symbol: None,
+            approved_account_ids: None,
+            last_transfer_at: None,
+        }
+    }
+}
+
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.token_id == other.token_id
+    }
+}
+
+impl Eq for Token {}
+
+impl PartialOrd for Token {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Token {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.token_id.cmp(&other.token_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(token_id: &str, owner_id: &str) -> Token {
+        Token::new(
+            token_id.into(),
+            Some(AccountId::new_unchecked(owner_id.to_string())),
+            U128(1),
+        )
+    }
+
+    #[test]
+    fn token_id_is_a_bare_string() {
+        let token_id = TokenId::from("gold");
+        assert_eq!(serde_json::to_string(&token_id).unwrap(), r#""gold""#);
+        assert_eq!(
+            serde_json::from_str::<TokenId>(r#""gold""#).unwrap(),
+            token_id
+        );
+        assert_eq!(
+            borsh::to_vec(&token_id).unwrap(),
+            borsh::to_vec(&"gold".to_string()).unwrap()
+        );
+        assert_eq!(token_id.to_string(), "gold");
+    }
+
+    #[test]
+    fn token_id_sorts_as_map_key() {
+        let map: BTreeMap<TokenId, u8> = [("b".into(), 2), ("c".into(), 3), ("a".into(), 1)]
+            .into_iter()
+            .collect();
+        let ids: Vec<&str> = map.keys().map(TokenId::as_str).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn token_json_keys() {
+        let value = serde_json::to_value(token("gold", "alice")).unwrap();
+        let keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "approved_account_ids",
+                "decimals",
+                "last_transfer_at",
+                "metadata",
+                "owner_id",
+                "supply",
+                "symbol",
+                "token_id"
+            ]
+        );
+        assert_eq!(value["owner_id"], "alice");
+        assert_eq!(value["supply"], "1");
+    }
+
+    #[test]
+    fn tokens_sort_by_id() {
+        let mut tokens = [token("b", "alice"), token("c", "alice"), token("a", "bob")];
+        tokens.sort();
+        let ids: Vec<&str> = tokens.iter().map(|t| t.token_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(token("a", "alice"), token("a", "bob"));
+    }
 }
@@ -0,0 +1,100 @@
+use crate::multi_token::core::MultiToken;
+use crate::multi_token::token::TokenId;
+use near_sdk::{require, Balance};
+
+impl MultiToken {
+    /// Panics with an `"Invariant violated"` message if the bookkeeping of `token_ids` has
+    /// drifted: every listed holder must have a balance and, with enumeration, list the
+    /// token too, and the balances must add up to the total supply, or stay within it
+    /// while some of it may be locked in escrow.
+    pub(crate) fn internal_validate(&self, token_ids: &[TokenId]) {
+        for token_id in token_ids {
+            let mut total: Balance = 0;
+            for holder_id in self
+                .owners_by_id
+                .get(token_id)
+                .iter()
+                .flat_map(|h| h.iter())
+            {
+                let balance = self
+                    .balances
+                    .get(&(holder_id.clone(), token_id.clone()))
+                    .unwrap_or(0);
+                require!(
+                    balance > 0,
+                    format!(
+                        "Invariant violated: {} is listed as a holder of {} without a balance",
+                        holder_id, token_id
+                    )
+                );
+                if let Some(tokens_per_owner) = self.tokens_per_owner.as_ref() {
+                    require!(
+                        tokens_per_owner
+                            .get(&holder_id)
+                            .is_some_and(|tokens| tokens.contains(token_id)),
+                        format!(
+                            "Invariant violated: {} is not listed as holding {}",
+                            holder_id, token_id
+                        )
+                    );
+                }
+                total = total.saturating_add(balance);
+            }
+            let supply = self.total_supply.get(token_id).unwrap_or(0);
+            require!(
+                if self.locked_balances.is_some() {
+                    total <= supply
+                } else {
+                    total == supply
+                },
+                format!(
+                    "Invariant violated: the balances of {} don't add up to its total supply",
+                    token_id
+                )
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_token::core::{MultiToken, MultiTokenCore};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Alice holds 100 gold.
+    fn setup() -> MultiToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut mt = MultiToken::new(b"m").with_enumeration();
+        mt.internal_mint(&accounts(0), &["gold".into()], &[100], None);
+        mt
+    }
+
+    #[test]
+    fn consistent_state_passes() {
+        let mut mt = setup();
+        mt.mt_transfer(accounts(1), "gold".into(), U128(100), None, None);
+        mt.internal_burn(&accounts(1), &["gold".into()], &[40], None);
+        assert_eq!(mt.mt_supply("gold".into()), Some(U128(60)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant violated: the balances of gold don't add up")]
+    fn drifted_supply_is_caught() {
+        let mut mt = setup();
+        mt.total_supply.insert(&"gold".into(), &99);
+        mt.mt_transfer(accounts(1), "gold".into(), U128(1), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant violated: alice is listed as a holder of gold")]
+    fn stale_holder_is_caught() {
+        let mut mt = setup();
+        mt.balances.remove(&(accounts(0), "gold".into()));
+        mt.internal_mint(&accounts(1), &["gold".into()], &[100], None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Helpers for unit tests of contracts built on this crate, behind the `testing` feature.
+//!
+//! They compare logged events as parsed JSON, so assertions don't depend on key order
+//! or whitespace:
+//!
+//! ```ignore
+//! assert_event(json!({
+//!     "standard": "nep246",
+//!     "version": "1.0.0",
+//!     "event": "mt_mint",
+//!     "data": [{"owner_id": "bob", "token_ids": ["0"]}],
+//! }));
+//! ```
+
+use near_sdk::{test_utils, AccountId};
+use serde_json::Value;
+
+/// Prefix NEP-297 puts in front of every event log line.
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// Returns `name` as an [`AccountId`], without validating it.
+pub fn account(name: &str) -> AccountId {
+    AccountId::new_unchecked(name.to_string())
+}
+
+/// Parses the most recent `EVENT_JSON:` log of the current test. Panics if nothing was
+/// logged as an event.
+pub fn last_event_json() -> Value {
+    let logs = test_utils::get_logs();
+    let json = logs
+        .iter()
+        .rev()
+        .find_map(|log| log.strip_prefix(EVENT_JSON_PREFIX))
+        .expect("No EVENT_JSON log was emitted");
+    serde_json::from_str(json).expect("EVENT_JSON log is not valid JSON")
+}
+
+/// Asserts the most recent event equals `expected`, envelope included.
+pub fn assert_event(expected: Value) {
+    assert_eq!(last_event_json(), expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn finds_the_last_event() {
+        near_sdk::env::log_str(r#"EVENT_JSON:{"event":"first"}"#);
+        near_sdk::env::log_str(r#"EVENT_JSON:{ "standard": "x", "event": "second" }"#);
+        near_sdk::env::log_str("plain log");
+        assert_event(json!({"event": "second", "standard": "x"}));
+    }
+
+    #[test]
+    #[should_panic(expected = "No EVENT_JSON log was emitted")]
+    fn panics_without_events() {
+        near_sdk::env::log_str("plain log");
+        last_event_json();
+    }
+}
@@ -0,0 +1,21 @@
+#![deny(unused_must_use)]
+
+use nep_246::multi_token::approval::MultiTokenApproval;
+use nep_246::multi_token::core::MultiTokenCore;
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+
+fn ignore_is_approved(
+    contract: &impl MultiTokenApproval,
+    owner_id: AccountId,
+    account_id: AccountId,
+) {
+    contract.mt_is_approved(vec!["1".into()], owner_id, account_id, vec![U128(1)], None);
+}
+
+fn ignore_views(contract: &impl MultiTokenCore, account_id: AccountId) {
+    contract.mt_token(vec!["1".into()]);
+    contract.mt_balance_of(account_id, "1".into());
+}
+
+fn main() {}